@@ -0,0 +1,554 @@
+//! A Brigadier-style command dispatcher.
+//!
+//! Commands are modeled as a tree of [`CommandNode`]s: `Literal` nodes match a
+//! fixed keyword, and `Argument` nodes consume and parse one token using an
+//! [`ArgumentKind`]. Any node may carry a `scope`, checked against the
+//! caller's granted scopes (via [`CommandScopeRegistry`]) before parsing is
+//! allowed to continue past it.
+//!
+//! [`CommandDispatcher::parse`] walks the tree, consuming tokens from a
+//! [`StringReader`] one node at a time, and returns the path of matched node
+//! names plus the parsed value of every `Argument` node along that path.
+//! [`CommandDispatcher::execute`] does the same walk and then runs the
+//! matched node's [`CommandNode::executes`] closure, and
+//! [`CommandDispatcher::get_completions`] walks as far as `input` matches to
+//! suggest the next token.
+//!
+//! # Example
+//! ```
+//! use kanden_command::dispatcher::{ArgumentKind, CommandDispatcher, CommandNode, ParsedValue};
+//! use kanden_command::scopes::CommandScopeRegistry;
+//!
+//! let mut registry = CommandScopeRegistry::new();
+//! registry.add_scope("kanden.command.teleport");
+//!
+//! let mut dispatcher = CommandDispatcher::new();
+//! dispatcher.register(
+//!     CommandNode::literal("tp")
+//!         .with_scope("kanden.command.teleport")
+//!         .then(CommandNode::argument("destination", ArgumentKind::PlayerSelector)),
+//! );
+//!
+//! let parsed = dispatcher
+//!     .parse("tp Notch", &["kanden.command.teleport"], &registry)
+//!     .unwrap();
+//!
+//! assert_eq!(parsed.path, vec!["tp", "destination"]);
+//! assert_eq!(
+//!     parsed.values.get("destination"),
+//!     Some(&ParsedValue::PlayerSelector("Notch".to_owned()))
+//! );
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy_ecs::prelude::Entity;
+use bevy_ecs::system::Resource;
+
+use crate::scopes::CommandScopeRegistry;
+
+/// A cursor over a command's raw text, consumed token-by-token while parsing.
+#[derive(Clone, Debug)]
+pub struct StringReader<'a> {
+    source: &'a str,
+    cursor: usize,
+}
+
+impl<'a> StringReader<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { source, cursor: 0 }
+    }
+
+    /// The unconsumed portion of the source string.
+    pub fn remaining(&self) -> &'a str {
+        &self.source[self.cursor..]
+    }
+
+    /// Whether there's nothing left to read but whitespace.
+    pub fn is_at_end(&self) -> bool {
+        self.remaining().trim_start().is_empty()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.remaining().trim_start();
+        self.cursor = self.source.len() - trimmed.len();
+    }
+
+    /// Reads the next whitespace-delimited token, or `""` if there's nothing
+    /// left to read.
+    pub fn read_unquoted_string(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let remaining = self.remaining();
+        let end = remaining
+            .find(char::is_whitespace)
+            .unwrap_or(remaining.len());
+        let token = &remaining[..end];
+        self.cursor += end;
+        token
+    }
+
+    /// Reads every remaining character, including whitespace. Used for
+    /// trailing "greedy string" arguments like a chat message.
+    pub fn read_remaining(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let remaining = self.remaining();
+        self.cursor = self.source.len();
+        remaining
+    }
+}
+
+/// The type of value an `Argument` node parses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArgumentKind {
+    Integer,
+    Float,
+    /// A single whitespace-delimited token.
+    String,
+    /// The rest of the input, including whitespace. Only useful on a node
+    /// with no children, since nothing is left to parse afterwards.
+    GreedyString,
+    /// A player-selector token (`@a`, `@p`, a username, ...).
+    ///
+    /// The token is captured as-is; resolving a selector into matching
+    /// players is a separate concern layered on top of parsing.
+    PlayerSelector,
+}
+
+impl ArgumentKind {
+    fn parse(self, reader: &mut StringReader) -> Result<ParsedValue, CommandParseError> {
+        match self {
+            ArgumentKind::Integer => {
+                let token = reader.read_unquoted_string();
+                token
+                    .parse()
+                    .map(ParsedValue::Integer)
+                    .map_err(|_| CommandParseError::InvalidArgument {
+                        token: token.to_owned(),
+                        expected: "an integer",
+                    })
+            }
+            ArgumentKind::Float => {
+                let token = reader.read_unquoted_string();
+                token
+                    .parse()
+                    .map(ParsedValue::Float)
+                    .map_err(|_| CommandParseError::InvalidArgument {
+                        token: token.to_owned(),
+                        expected: "a float",
+                    })
+            }
+            ArgumentKind::String => {
+                let token = reader.read_unquoted_string();
+                if token.is_empty() {
+                    Err(CommandParseError::InvalidArgument {
+                        token: token.to_owned(),
+                        expected: "a string",
+                    })
+                } else {
+                    Ok(ParsedValue::String(token.to_owned()))
+                }
+            }
+            ArgumentKind::GreedyString => {
+                let rest = reader.read_remaining();
+                if rest.is_empty() {
+                    Err(CommandParseError::InvalidArgument {
+                        token: rest.to_owned(),
+                        expected: "a string",
+                    })
+                } else {
+                    Ok(ParsedValue::String(rest.to_owned()))
+                }
+            }
+            ArgumentKind::PlayerSelector => {
+                let token = reader.read_unquoted_string();
+                if token.is_empty() {
+                    Err(CommandParseError::InvalidArgument {
+                        token: token.to_owned(),
+                        expected: "a player selector",
+                    })
+                } else {
+                    Ok(ParsedValue::PlayerSelector(token.to_owned()))
+                }
+            }
+        }
+    }
+}
+
+/// The parsed value of an `Argument` node.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ParsedValue {
+    Integer(i32),
+    Float(f64),
+    String(String),
+    PlayerSelector(String),
+}
+
+pub(crate) enum NodeKind {
+    Literal(String),
+    Argument { name: String, kind: ArgumentKind },
+}
+
+/// The argument values and invoking entity passed to a node's stored
+/// executor once its command fully matches. Mirrors [`ParsedCommand`], plus
+/// the `source` the dispatcher was asked to run the command for.
+pub struct CommandContext {
+    /// The entity that issued the command.
+    pub source: Entity,
+    pub path: Vec<String>,
+    pub values: BTreeMap<String, ParsedValue>,
+}
+
+impl CommandContext {
+    /// The parsed value bound to `name`, if an `Argument` node by that name
+    /// was on the matched path.
+    pub fn value(&self, name: &str) -> Option<&ParsedValue> {
+        self.values.get(name)
+    }
+}
+
+/// Returned by a node's stored executor when it fails for a reason the
+/// parser can't catch itself (e.g. the source entity no longer exists, or a
+/// precondition specific to that command wasn't met).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandExecutionError(pub String);
+
+impl fmt::Display for CommandExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CommandExecutionError {}
+
+/// A node's stored behavior, run once its command fully matches and the
+/// `source` has been checked against every scope on the matched path.
+///
+/// Returns a Brigadier-style result count (conventionally the number of
+/// entities/things affected) on success.
+pub type CommandExecutor = dyn Fn(&mut CommandContext) -> Result<i32, CommandExecutionError> + Send + Sync;
+
+/// A node in a [`CommandDispatcher`]'s command tree.
+pub struct CommandNode {
+    pub(crate) kind: NodeKind,
+    pub(crate) scope: Option<String>,
+    pub(crate) children: Vec<CommandNode>,
+    pub(crate) executor: Option<Box<CommandExecutor>>,
+}
+
+impl CommandNode {
+    /// A node that matches the fixed keyword `name`.
+    pub fn literal(name: impl Into<String>) -> Self {
+        Self {
+            kind: NodeKind::Literal(name.into()),
+            scope: None,
+            children: Vec::new(),
+            executor: None,
+        }
+    }
+
+    /// A node that consumes and parses one token as `kind`, binding it to
+    /// `name` in the parsed command's values.
+    pub fn argument(name: impl Into<String>, kind: ArgumentKind) -> Self {
+        Self {
+            kind: NodeKind::Argument {
+                name: name.into(),
+                kind,
+            },
+            scope: None,
+            children: Vec::new(),
+            executor: None,
+        }
+    }
+
+    /// Requires `scope` to be granted (per [`CommandScopeRegistry::any_grants`])
+    /// for parsing to continue past this node.
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// Adds a child node, reached after this one matches.
+    pub fn then(mut self, child: CommandNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Stores `executor`, run by [`CommandDispatcher::execute`] when this
+    /// node is the last one matched by the input.
+    pub fn executes<F>(mut self, executor: F) -> Self
+    where
+        F: Fn(&mut CommandContext) -> Result<i32, CommandExecutionError> + Send + Sync + 'static,
+    {
+        self.executor = Some(Box::new(executor));
+        self
+    }
+
+    fn name(&self) -> &str {
+        match &self.kind {
+            NodeKind::Literal(name) => name,
+            NodeKind::Argument { name, .. } => name,
+        }
+    }
+}
+
+/// The result of successfully parsing a command: the names of every node
+/// matched, in order, and the value of every `Argument` node along that path.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct ParsedCommand {
+    pub path: Vec<String>,
+    pub values: BTreeMap<String, ParsedValue>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CommandParseError {
+    /// The input was empty or only whitespace. Input that ends on a node
+    /// without an executor still parses; [`CommandDispatcher::execute`]
+    /// reports that case as [`CommandDispatchError::NotExecutable`].
+    IncompleteCommand,
+    /// No registered node matched the next token.
+    NoMatchingNode,
+    /// An `Argument` node matched a token but failed to parse it.
+    InvalidArgument {
+        token: String,
+        expected: &'static str,
+    },
+    /// A node along the matched path requires a scope the caller wasn't
+    /// granted.
+    MissingScope(String),
+}
+
+impl fmt::Display for CommandParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandParseError::IncompleteCommand => {
+                write!(f, "command ended before a terminal node was reached")
+            }
+            CommandParseError::NoMatchingNode => {
+                write!(f, "no command node matched the input")
+            }
+            CommandParseError::InvalidArgument { token, expected } => {
+                write!(f, "expected {expected}, got `{token}`")
+            }
+            CommandParseError::MissingScope(scope) => {
+                write!(f, "missing required scope `{scope}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandParseError {}
+
+/// The ways [`CommandDispatcher::execute`] can fail beyond a plain parse
+/// error.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandDispatchError {
+    Parse(CommandParseError),
+    /// The matched node has no [`CommandNode::executes`] closure — e.g. a
+    /// literal used only to group subcommands, reached with nothing left to
+    /// parse.
+    NotExecutable,
+    Execution(CommandExecutionError),
+}
+
+impl fmt::Display for CommandDispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandDispatchError::Parse(err) => write!(f, "{err}"),
+            CommandDispatchError::NotExecutable => {
+                write!(f, "command ended on a node with no executor")
+            }
+            CommandDispatchError::Execution(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CommandDispatchError {}
+
+impl From<CommandParseError> for CommandDispatchError {
+    fn from(err: CommandParseError) -> Self {
+        CommandDispatchError::Parse(err)
+    }
+}
+
+/// A tree of [`CommandNode`]s, parsed against with [`CommandDispatcher::parse`]
+/// or run with [`CommandDispatcher::execute`].
+#[derive(Default, Resource)]
+pub struct CommandDispatcher {
+    roots: Vec<CommandNode>,
+}
+
+impl CommandDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a root node (and its subtree).
+    pub fn register(&mut self, node: CommandNode) {
+        self.roots.push(node);
+    }
+
+    /// The registered root nodes, in registration order.
+    pub fn roots(&self) -> &[CommandNode] {
+        &self.roots
+    }
+
+    /// Parses `input` against the registered command tree.
+    ///
+    /// `scopes` is the set of scopes the caller has been granted; every node
+    /// along the matched path that declares a `scope` must be granted by at
+    /// least one of them (checked via
+    /// [`CommandScopeRegistry::any_grants`]).
+    pub fn parse(
+        &self,
+        input: &str,
+        scopes: &[&str],
+        registry: &CommandScopeRegistry,
+    ) -> Result<ParsedCommand, CommandParseError> {
+        self.parse_matched(input, scopes, registry)
+            .map(|(parsed, _)| parsed)
+    }
+
+    /// Parses `input` for `source` and runs the matched node's stored
+    /// executor.
+    ///
+    /// Scope checks happen identically to [`Self::parse`] before the
+    /// executor ever runs, so a node's closure never sees a `source` that
+    /// wasn't granted its scope.
+    pub fn execute(
+        &self,
+        input: &str,
+        source: Entity,
+        scopes: &[&str],
+        registry: &CommandScopeRegistry,
+    ) -> Result<i32, CommandDispatchError> {
+        let (parsed, node) = self.parse_matched(input, scopes, registry)?;
+        let executor = node.executor.as_deref().ok_or(CommandDispatchError::NotExecutable)?;
+
+        let mut context = CommandContext {
+            source,
+            path: parsed.path,
+            values: parsed.values,
+        };
+
+        executor(&mut context).map_err(CommandDispatchError::Execution)
+    }
+
+    /// Suggests how `input` could be completed: the names of every child of
+    /// whatever node `input` matches so far whose name starts with the
+    /// partial final token, restricted to the nodes `scopes` can reach.
+    ///
+    /// Only `Literal` children are suggested — `Argument` nodes have no
+    /// value-suggestion hook in this crate.
+    pub fn get_completions(
+        &self,
+        input: &str,
+        scopes: &[&str],
+        registry: &CommandScopeRegistry,
+    ) -> Vec<String> {
+        let ends_with_boundary = input.is_empty() || input.ends_with(char::is_whitespace);
+        let mut tokens: Vec<&str> = input.split_whitespace().collect();
+        let partial = if ends_with_boundary {
+            ""
+        } else {
+            tokens.pop().unwrap_or("")
+        };
+
+        let mut candidates = &self.roots;
+        for token in tokens {
+            let mut reader = StringReader::new(token);
+            let Some((node, _)) = Self::match_one(candidates, &mut reader) else {
+                return Vec::new();
+            };
+
+            if let Some(scope) = &node.scope {
+                if !registry.any_grants(&scopes.to_vec(), scope) {
+                    return Vec::new();
+                }
+            }
+
+            candidates = &node.children;
+        }
+
+        candidates
+            .iter()
+            .filter(|node| match &node.scope {
+                Some(scope) => registry.any_grants(&scopes.to_vec(), scope),
+                None => true,
+            })
+            .filter_map(|node| match &node.kind {
+                NodeKind::Literal(name) if name.starts_with(partial) => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Shared walk behind [`Self::parse`] and [`Self::execute`]: matches
+    /// `input` against the tree and returns the parsed command alongside
+    /// the last node matched, so [`Self::execute`] can run its executor.
+    fn parse_matched(
+        &self,
+        input: &str,
+        scopes: &[&str],
+        registry: &CommandScopeRegistry,
+    ) -> Result<(ParsedCommand, &CommandNode), CommandParseError> {
+        let mut reader = StringReader::new(input);
+        let mut path = Vec::new();
+        let mut values = BTreeMap::new();
+        let mut candidates = &self.roots;
+
+        loop {
+            if reader.is_at_end() {
+                return Err(CommandParseError::IncompleteCommand);
+            }
+
+            let (node, value) =
+                Self::match_one(candidates, &mut reader).ok_or(CommandParseError::NoMatchingNode)?;
+
+            if let Some(scope) = &node.scope {
+                if !registry.any_grants(&scopes.to_vec(), scope) {
+                    return Err(CommandParseError::MissingScope(scope.clone()));
+                }
+            }
+
+            path.push(node.name().to_owned());
+            if let Some(value) = value {
+                values.insert(node.name().to_owned(), value);
+            }
+
+            if reader.is_at_end() || node.children.is_empty() {
+                return Ok((ParsedCommand { path, values }, node));
+            }
+
+            candidates = &node.children;
+        }
+    }
+
+    /// Tries each candidate in registration order, committing `reader` to
+    /// the first one that matches.
+    fn match_one<'a>(
+        candidates: &'a [CommandNode],
+        reader: &mut StringReader,
+    ) -> Option<(&'a CommandNode, Option<ParsedValue>)> {
+        for node in candidates {
+            let mut attempt = reader.clone();
+
+            match &node.kind {
+                NodeKind::Literal(name) => {
+                    if attempt.read_unquoted_string() == name {
+                        *reader = attempt;
+                        return Some((node, None));
+                    }
+                }
+                NodeKind::Argument { kind, .. } => {
+                    if let Ok(value) = kind.parse(&mut attempt) {
+                        *reader = attempt;
+                        return Some((node, Some(value)));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
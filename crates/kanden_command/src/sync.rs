@@ -0,0 +1,136 @@
+//! Per-client synchronization of the command tree declared by a
+//! [`CommandDispatcher`] with the protocol's `Commands` (declare-commands)
+//! packet.
+//!
+//! Every client only ever sees the nodes it's been granted the scope for:
+//! [`build_filtered_tree`] walks the dispatcher's tree once per client,
+//! pruning any node whose `scope` [`CommandScopeRegistry::any_grants`]
+//! rejects, so the redacted tree never leaks the existence of admin-only
+//! commands. [`sync_command_tree`] re-runs this for every client whose
+//! [`CommandScopes`] changed, mirroring [`add_new_scopes`](crate::scopes).
+
+use bevy_ecs::prelude::{Changed, Component, Query, Res};
+use kanden_protocol::packets::play::commands_s2c::{
+    CommandNode as PacketNode, CommandNodeData, CommandParser, CommandsS2c, StringParserKind,
+};
+use kanden_protocol::{VarInt, WritePacket};
+
+use crate::dispatcher::{ArgumentKind, CommandDispatcher, CommandNode, NodeKind};
+use crate::scopes::{CommandScopeRegistry, CommandScopes};
+
+fn to_parser(kind: &ArgumentKind) -> CommandParser {
+    match kind {
+        ArgumentKind::Integer => CommandParser::Integer {
+            min: None,
+            max: None,
+        },
+        ArgumentKind::Float => CommandParser::Float {
+            min: None,
+            max: None,
+        },
+        // A single whitespace-delimited token -- `SINGLE_WORD`, not
+        // `QUOTABLE_PHRASE`, since `ArgumentKind::String` never unescapes
+        // quotes on parse (see `ArgumentKind::parse`).
+        ArgumentKind::String => CommandParser::String(StringParserKind::SingleWord),
+        ArgumentKind::GreedyString => CommandParser::String(StringParserKind::GreedyPhrase),
+        // The dispatcher captures the selector token as-is rather than
+        // resolving it, so neither restriction is enforced client-side.
+        ArgumentKind::PlayerSelector => CommandParser::Entity {
+            single: false,
+            players_only: false,
+        },
+    }
+}
+
+/// Builds the `CommandsS2c` packet a client holding `scopes` should see:
+/// every node reachable from `dispatcher`'s roots whose `scope` (if any) is
+/// granted by `scopes`, under a synthetic root node at index 0.
+pub fn build_filtered_tree(
+    dispatcher: &CommandDispatcher,
+    scopes: &[&str],
+    registry: &CommandScopeRegistry,
+) -> CommandsS2c<'static> {
+    let mut nodes = vec![PacketNode {
+        children: Vec::new(),
+        redirect_node: None,
+        executable: false,
+        data: CommandNodeData::Root,
+    }];
+
+    let mut root_children = Vec::new();
+    for root in dispatcher.roots() {
+        if let Some(index) = push_node(root, scopes, registry, &mut nodes) {
+            root_children.push(VarInt(index as i32));
+        }
+    }
+    nodes[0].children = root_children;
+
+    CommandsS2c {
+        nodes,
+        root_index: VarInt(0),
+    }
+}
+
+/// Recursively pushes `node` and its grantable children into `nodes`,
+/// returning `node`'s own index, or `None` if its scope was rejected (in
+/// which case its whole subtree is dropped, not just the node itself).
+fn push_node(
+    node: &CommandNode,
+    scopes: &[&str],
+    registry: &CommandScopeRegistry,
+    nodes: &mut Vec<PacketNode<'static>>,
+) -> Option<usize> {
+    if let Some(scope) = node.scope.as_deref() {
+        if !registry.any_grants(&scopes.to_vec(), scope) {
+            return None;
+        }
+    }
+
+    let data = match &node.kind {
+        NodeKind::Literal(name) => CommandNodeData::Literal {
+            name: name.clone().into(),
+        },
+        NodeKind::Argument { name, kind } => CommandNodeData::Argument {
+            name: name.clone().into(),
+            parser: to_parser(kind),
+        },
+    };
+
+    let index = nodes.len();
+    nodes.push(PacketNode {
+        children: Vec::new(),
+        // No alias mechanism exists on `CommandNode` yet -- see the comment
+        // on `CommandNodeData`/`CommandNode` in `commands_s2c.rs`.
+        redirect_node: None,
+        executable: node.executor.is_some(),
+        data,
+    });
+
+    let mut children = Vec::new();
+    for child in &node.children {
+        if let Some(child_index) = push_node(child, scopes, registry, nodes) {
+            children.push(VarInt(child_index as i32));
+        }
+    }
+    nodes[index].children = children;
+
+    Some(index)
+}
+
+/// Re-sends each client's filtered command tree whenever its
+/// [`CommandScopes`] changes.
+///
+/// Generic over `C` so it can run against whatever component a concrete
+/// client implements [`WritePacket`] for; this crate doesn't have visibility
+/// into that type.
+pub fn sync_command_tree<C: Component + WritePacket>(
+    dispatcher: Res<CommandDispatcher>,
+    registry: Res<CommandScopeRegistry>,
+    mut clients: Query<(&CommandScopes, &mut C), Changed<CommandScopes>>,
+) {
+    for (scopes, mut client) in clients.iter_mut() {
+        let granted: Vec<&str> = scopes.iter().map(String::as_str).collect();
+        let packet = build_filtered_tree(&dispatcher, &granted, &registry);
+        client.write_packet(&packet);
+    }
+}
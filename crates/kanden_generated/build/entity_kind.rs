@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use heck::ToUpperCamelCase;
+use kanden_build_utils::rerun_if_changed;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Entities {
+    #[serde(flatten)]
+    kinds: BTreeMap<String, u32>,
+}
+
+/// Generates the `EntityKind` enum from `entities.json`, plus `Encode`/
+/// `Decode` impls that map variants to the numeric entity-type ID Pumpkin
+/// assigned them.
+///
+/// Entity-type IDs are per data-generation run, not per protocol version, so
+/// (unlike packet IDs) a single numeric mapping is enough today; if a future
+/// version needs the ID to vary by negotiated `ProtocolVersion` the match
+/// arms here are where that dispatch would be added.
+pub(crate) fn build() -> anyhow::Result<TokenStream> {
+    rerun_if_changed(["extracted/entities.json"]);
+
+    let entities =
+        serde_json::from_str::<Entities>(include_str!("../extracted/entities.json"))?;
+
+    let mut sorted_kinds = entities.kinds.iter().collect::<Vec<_>>();
+    sorted_kinds.sort_by_key(|(_, id)| **id);
+
+    let mut variants = TokenStream::new();
+    let mut id_arms = TokenStream::new();
+    let mut from_id_arms = TokenStream::new();
+
+    for (name, id) in sorted_kinds {
+        let variant_name = name
+            .strip_prefix("minecraft:")
+            .unwrap_or(name)
+            .to_upper_camel_case();
+        let variant_ident = format_ident!("{variant_name}");
+        let id = *id as i32;
+
+        variants.extend(quote! { #variant_ident, });
+        id_arms.extend(quote! { Self::#variant_ident => #id, });
+        from_id_arms.extend(quote! { #id => Some(Self::#variant_ident), });
+    }
+
+    Ok(quote! {
+        #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+        pub enum EntityKind {
+            #variants
+        }
+
+        impl EntityKind {
+            /// Returns the numeric registry ID assigned to this entity kind.
+            pub const fn id(self) -> i32 {
+                match self {
+                    #id_arms
+                }
+            }
+
+            /// Looks up the entity kind for a numeric registry ID.
+            pub fn from_id(id: i32) -> Option<Self> {
+                match id {
+                    #from_id_arms
+                    _ => None,
+                }
+            }
+        }
+
+        impl kanden_protocol::Encode for EntityKind {
+            fn encode(&self, w: impl std::io::Write) -> anyhow::Result<()> {
+                kanden_protocol::VarInt(self.id()).encode(w)
+            }
+        }
+
+        impl<'a> kanden_protocol::Decode<'a> for EntityKind {
+            fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+                let id = kanden_protocol::VarInt::decode(r)?.0;
+                Self::from_id(id).ok_or_else(|| anyhow::anyhow!("invalid entity kind id {id}"))
+            }
+        }
+    })
+}
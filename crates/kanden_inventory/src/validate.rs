@@ -2,11 +2,11 @@ use kanden_server::protocol::anyhow::{self, ensure};
 use kanden_server::protocol::packets::play::container_click_c2s::{ClickMode, SlotChange};
 use kanden_server::protocol::packets::play::ContainerClickC2s;
 use kanden_server::protocol::VarInt;
-use kanden_server::ItemStack;
+use kanden_server::{ItemKind, ItemStack};
 
 use crate::player_inventory::PlayerInventory;
 use crate::validate::anyhow::bail;
-use crate::{CursorItem, Inventory, InventoryWindow};
+use crate::{CursorItem, Inventory, InventoryWindow, InventoryWindowMut};
 
 /// This function simulates the "item click" action on the server
 /// and validates it.
@@ -15,12 +15,180 @@ use crate::{CursorItem, Inventory, InventoryWindow};
 ///
 /// We need to compute those values in the validation because the packet no
 /// longer contains this data (item stacks are hashed now).
+///
+/// `drag_session` tracks an in-progress [`ClickMode::Drag`] gesture across
+/// its start/add-slot/end packets. Callers must keep one per player for the
+/// lifetime of their connection and pass the same instance for every packet
+/// from that player.
+///
+/// Failures are classified via [`ClickValidationError`] rather than
+/// propagated as a bare error: a [`validate_click_slot_fields`] failure
+/// means the packet itself is malformed (a [`ClickValidationError::ProtocolViolation`]),
+/// while a failure in the conservation-of-mass/semantic checks or the
+/// unhash step more likely means the client's view of the window is stale
+/// (a [`ClickValidationError::RecoverableDesync`]) — see [`DesyncTracker`]
+/// for how a caller should act on that distinction.
 pub(super) fn validate_click_slot_packet(
     packet: &ContainerClickC2s,
     player_inventory: &Inventory,
     open_inventory: Option<&Inventory>,
     cursor_item: &CursorItem,
-) -> anyhow::Result<(ItemStack, Vec<SlotChange>)> {
+    drag_session: &mut DragSession,
+) -> Result<(ItemStack, Vec<SlotChange>), ClickValidationError> {
+    let max_slot = validate_click_slot_fields(packet, player_inventory, open_inventory)
+        .map_err(ClickValidationError::ProtocolViolation)?;
+
+    validate_click_slot_semantics(
+        packet,
+        player_inventory,
+        open_inventory,
+        cursor_item,
+        drag_session,
+        max_slot,
+    )
+    .map_err(ClickValidationError::RecoverableDesync)
+}
+
+/// A [`validate_click_slot_packet`] failure, classified by how a caller
+/// should respond to it.
+#[derive(Debug)]
+pub(super) enum ClickValidationError {
+    /// The packet's fields violate bounds no legitimate client could
+    /// produce regardless of desync (an out-of-range slot index, an
+    /// invalid button, ...). Not worth tolerating.
+    ProtocolViolation(anyhow::Error),
+    /// The packet is well-formed but doesn't match the server's view of
+    /// the window — the kind of mismatch a stale or reordered client
+    /// packet produces. Recoverable by resyncing the client's view (see
+    /// [`build_resync`]) instead of disconnecting it.
+    RecoverableDesync(anyhow::Error),
+}
+
+impl std::fmt::Display for ClickValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProtocolViolation(e) => write!(f, "protocol violation: {e:#}"),
+            Self::RecoverableDesync(e) => write!(f, "recoverable desync: {e:#}"),
+        }
+    }
+}
+
+impl std::error::Error for ClickValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ProtocolViolation(e) | Self::RecoverableDesync(e) => Some(e.as_ref()),
+        }
+    }
+}
+
+/// What a caller should do in response to a [`ClickValidationError`], as
+/// decided by [`DesyncTracker::record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ClickRecovery {
+    /// Re-send the authoritative window/cursor state built by
+    /// [`build_resync`] and reconcile future clicks against it.
+    Resync,
+    /// The client has violated the protocol, or desynced too many times to
+    /// keep tolerating; disconnect it.
+    Kick,
+}
+
+/// Counts how many recoverable desyncs a player's window has triggered, so
+/// repeated desyncs — the standard symptom of a client trying to exploit an
+/// item-duplication-via-desync bug rather than a one-off stale packet — are
+/// escalated to a kick instead of being resynced forever.
+///
+/// Callers should keep one per open window (alongside a [`DragSession`]) and
+/// call [`DesyncTracker::reset`] after a click that validates cleanly.
+#[derive(Debug)]
+pub(super) struct DesyncTracker {
+    count: u32,
+    threshold: u32,
+}
+
+impl Default for DesyncTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_DESYNC_KICK_THRESHOLD)
+    }
+}
+
+const DEFAULT_DESYNC_KICK_THRESHOLD: u32 = 8;
+
+impl DesyncTracker {
+    pub(super) fn new(threshold: u32) -> Self {
+        Self { count: 0, threshold }
+    }
+
+    /// Records a [`ClickValidationError`] and decides how the caller should
+    /// respond. A [`ClickValidationError::ProtocolViolation`] always kicks;
+    /// a [`ClickValidationError::RecoverableDesync`] resyncs until
+    /// `threshold` consecutive desyncs have been recorded, then kicks too.
+    pub(super) fn record(&mut self, error: &ClickValidationError) -> ClickRecovery {
+        match error {
+            ClickValidationError::ProtocolViolation(_) => ClickRecovery::Kick,
+            ClickValidationError::RecoverableDesync(_) => {
+                self.count += 1;
+                if self.count > self.threshold {
+                    ClickRecovery::Kick
+                } else {
+                    ClickRecovery::Resync
+                }
+            }
+        }
+    }
+
+    /// Clears the desync count. Call this after a click validates cleanly,
+    /// so an old desync doesn't count towards a much later one.
+    pub(super) fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+/// The authoritative state a resync pushes back to a desynced client: every
+/// slot's true contents plus the cursor item, and a bumped state/action id
+/// so the client's next click is validated against this view.
+#[derive(Debug, Clone)]
+pub(super) struct InventoryResync {
+    /// Every slot's authoritative contents, indexed the same way
+    /// [`SlotChange::idx`] indexes slots.
+    pub slots: Vec<ItemStack>,
+    pub cursor_item: ItemStack,
+    /// The state/action id the client's next click should be validated
+    /// against.
+    pub state_id: i32,
+}
+
+/// Builds the resync payload for `window`/`cursor_item` and bumps
+/// `*state_id`, the caller-owned counter tracking which state/action id the
+/// client is expected to reconcile its next click against.
+///
+/// This doesn't send anything itself — `kanden_protocol`'s packet for a
+/// full inventory resync (something like `SetContainerContentS2c`) isn't
+/// present in this checkout, so writing it to the client is left to the
+/// caller.
+pub(super) fn build_resync(
+    window: &InventoryWindow,
+    cursor_item: &CursorItem,
+    max_slot: u16,
+    state_id: &mut i32,
+) -> InventoryResync {
+    *state_id = state_id.wrapping_add(1);
+
+    InventoryResync {
+        slots: (0..=max_slot).map(|idx| window.slot(idx).clone()).collect(),
+        cursor_item: cursor_item.0.clone(),
+        state_id: *state_id,
+    }
+}
+
+/// Checks that `packet`'s fields are within the bounds any well-formed
+/// client packet must satisfy, regardless of what the server's view of the
+/// window currently is. Returns the window's slot count on success.
+fn validate_click_slot_fields(
+    packet: &ContainerClickC2s,
+    player_inventory: &Inventory,
+    open_inventory: Option<&Inventory>,
+) -> anyhow::Result<u16> {
     ensure!(
         (packet.window_id == VarInt(0)) == open_inventory.is_none(),
         "window id and open inventory mismatch: window_id: {} open_inventory: {}",
@@ -28,8 +196,6 @@ pub(super) fn validate_click_slot_packet(
         open_inventory.is_some()
     );
 
-    let mut new_slot_changes = Vec::with_capacity(packet.slot_changes.len());
-
     let max_slot = if let Some(open_inv) = open_inventory {
         // when the window is split, we can only access the main slots of player's
         // inventory
@@ -124,9 +290,33 @@ pub(super) fn validate_click_slot_packet(
                 "invalid slot index"
             )
         }
-        ClickMode::DoubleClick => ensure!(packet.button == 0, "invalid button"),
+        ClickMode::DoubleClick => {
+            ensure!(packet.button == 0, "invalid button");
+            ensure!(
+                (0..=max_slot).contains(&(packet.slot_idx as u16)),
+                "invalid slot index"
+            )
+        }
     }
 
+    Ok(max_slot)
+}
+
+/// Checks that `packet` is internally consistent with the server's current
+/// view of the window (conservation of mass, swap/merge/transmute shape,
+/// drag distribution, double-click collection), then reconstructs the full
+/// item stacks the hashed packet refers to.
+#[allow(clippy::too_many_arguments)]
+fn validate_click_slot_semantics(
+    packet: &ContainerClickC2s,
+    player_inventory: &Inventory,
+    open_inventory: Option<&Inventory>,
+    cursor_item: &CursorItem,
+    drag_session: &mut DragSession,
+    max_slot: u16,
+) -> anyhow::Result<(ItemStack, Vec<SlotChange>)> {
+    let mut new_slot_changes = Vec::with_capacity(packet.slot_changes.len());
+
     // Check that items aren't being duplicated, i.e. conservation of mass.
 
     let window = InventoryWindow {
@@ -273,6 +463,62 @@ pub(super) fn validate_click_slot_packet(
                         .all(|s| s.stack.item == item_kind),
                     "shift click must move the same item kind"
                 );
+
+                // assert the item landed on a legal destination for its role, not
+                // just any same-kind pair of slots
+                let destination_roles: Vec<SlotRole> = packet
+                    .slot_changes
+                    .iter()
+                    .filter(|s| !s.stack.is_empty())
+                    .map(|s| slot_role(&window, s.idx as u16))
+                    .collect();
+
+                let source_role = slot_role(&window, packet.slot_idx as u16);
+                // The preferred slot only applies when it is a different, empty
+                // slot of this window; otherwise the generic routing below applies.
+                let preferred = preferred_slot_role(item_kind).filter(|&role| {
+                    role != source_role
+                        && (0..=max_slot)
+                            .any(|idx| slot_role(&window, idx) == role && window.slot(idx).is_empty())
+                });
+
+                if let Some(preferred) = preferred {
+                    ensure!(
+                        destination_roles.contains(&preferred),
+                        "shift click of this item must land on its {:?} slot",
+                        preferred
+                    );
+                } else {
+                    let expected_roles: &[SlotRole] = match source_role {
+                        SlotRole::Container => &[SlotRole::Main, SlotRole::Hotbar],
+                        SlotRole::Main => {
+                            if window.open_inventory.is_some() {
+                                &[SlotRole::Container]
+                            } else {
+                                &[SlotRole::Hotbar]
+                            }
+                        }
+                        SlotRole::Hotbar => {
+                            if window.open_inventory.is_some() {
+                                &[SlotRole::Container]
+                            } else {
+                                &[SlotRole::Main]
+                            }
+                        }
+                        // Shift-clicking out of an armor, offhand, or crafting-output
+                        // slot can reasonably land in either half of the player's own
+                        // inventory; without item metadata to narrow this further (see
+                        // `preferred_slot_role`), accept either.
+                        _ => &[SlotRole::Main, SlotRole::Hotbar, SlotRole::Container],
+                    };
+
+                    ensure!(
+                        destination_roles
+                            .iter()
+                            .all(|role| expected_roles.contains(role)),
+                        "shift click landed outside the slots it should route to"
+                    );
+                }
             }
         }
 
@@ -371,28 +617,157 @@ pub(super) fn validate_click_slot_packet(
             }
         }
         ClickMode::Drag => {
-            if matches!(packet.button, 2 | 6 | 10) {
-                let count_deltas = calculate_net_item_delta(packet, &window, cursor_item);
+            let kind = match packet.button / 4 {
+                0 => DragKind::EvenSplit,
+                1 => DragKind::OnePerSlot,
+                2 => DragKind::CreativeClone,
+                _ => unreachable!(),
+            };
+
+            match packet.button % 4 {
+                0 => {
+                    // Start event: begin tracking a new drag, discarding any
+                    // prior one that never reached an end event.
+                    ensure!(
+                        packet.slot_idx == -999 && packet.slot_changes.is_empty(),
+                        "drag start must not touch any slots"
+                    );
+                    ensure!(
+                        packet.carried_item.item == cursor_item.0.item
+                            && packet.carried_item.count == cursor_item.0.count,
+                        "drag start must not change the carried item"
+                    );
+
+                    drag_session.active = Some(ActiveDrag {
+                        kind,
+                        painted: Vec::new(),
+                    });
+                }
+                1 => {
+                    // Add-slot event: marks one more slot as painted.
+                    let Some(active) = drag_session.active.as_mut() else {
+                        bail!("drag add-slot with no drag in progress");
+                    };
+                    ensure!(
+                        kind == active.kind,
+                        "drag add-slot does not match the drag that was started"
+                    );
+                    ensure!(
+                        (0..=max_slot).contains(&(packet.slot_idx as u16)),
+                        "drag add-slot must target a real slot"
+                    );
+                    ensure!(
+                        packet.slot_changes.is_empty()
+                            && packet.carried_item.item == cursor_item.0.item
+                            && packet.carried_item.count == cursor_item.0.count,
+                        "drag add-slot must not change the carried item"
+                    );
+
+                    let slot_idx = packet.slot_idx as u16;
+                    if !active.painted.contains(&slot_idx) {
+                        active.painted.push(slot_idx);
+                    }
+                }
+                _ => {
+                    // End event: apply the distribution to every painted slot.
+                    let Some(active) = drag_session.active.take() else {
+                        bail!("drag end with no drag in progress");
+                    };
+                    ensure!(
+                        kind == active.kind,
+                        "drag end button does not match the drag that was started"
+                    );
+
+                    let distribution = simulate_drag_distribution(
+                        &window,
+                        cursor_item,
+                        active.kind,
+                        &active.painted,
+                    )?;
+
+                    ensure!(
+                        packet.slot_changes.len() == distribution.painted.len(),
+                        "drag end touched {} slots, expected {}",
+                        packet.slot_changes.len(),
+                        distribution.painted.len()
+                    );
+
+                    for painted in &distribution.painted {
+                        let Some(reported) = packet
+                            .slot_changes
+                            .iter()
+                            .find(|s| s.idx as u16 == painted.idx)
+                        else {
+                            bail!("drag end did not touch painted slot {}", painted.idx);
+                        };
+
+                        ensure!(
+                            reported.stack.item == painted.new_stack.item
+                                && reported.stack.count == painted.new_stack.count,
+                            "drag end left slot {} as {:?}, expected {:?}",
+                            painted.idx,
+                            reported.stack,
+                            painted.new_stack
+                        );
+                    }
+
+                    ensure!(
+                        packet.carried_item.item == distribution.cursor.item
+                            && packet.carried_item.count == distribution.cursor.count,
+                        "drag end carried item {:?}, expected {:?}",
+                        packet.carried_item,
+                        distribution.cursor
+                    );
+                }
+            }
+        }
+        ClickMode::DoubleClick => {
+            let target_item = if !cursor_item.is_empty() {
+                cursor_item.item
+            } else {
+                let clicked = window.slot(packet.slot_idx as u16);
                 ensure!(
-                    count_deltas == 0,
-                    "invalid item delta: expected 0, got {}",
-                    count_deltas
+                    !clicked.is_empty(),
+                    "double click on an empty slot with an empty cursor"
                 );
-            } else {
+                clicked.item
+            };
+
+            let collect =
+                simulate_double_click_collect(&window, cursor_item, target_item, max_slot);
+
+            ensure!(
+                packet.slot_changes.len() == collect.drained.len(),
+                "double click touched {} slots, expected {}",
+                packet.slot_changes.len(),
+                collect.drained.len()
+            );
+
+            for drained in &collect.drained {
+                let Some(reported) = packet
+                    .slot_changes
+                    .iter()
+                    .find(|s| s.idx as u16 == drained.idx)
+                else {
+                    bail!("double click did not touch slot {}", drained.idx);
+                };
+
                 ensure!(
-                    packet.slot_changes.is_empty()
-                        && packet.carried_item.item == cursor_item.0.item
-                        && packet.carried_item.count == cursor_item.0.count,
-                    "invalid drag state"
+                    reported.stack.item == drained.remaining.item
+                        && reported.stack.count == drained.remaining.count,
+                    "double click left slot {} as {:?}, expected {:?}",
+                    drained.idx,
+                    reported.stack,
+                    drained.remaining
                 );
             }
-        }
-        ClickMode::DoubleClick => {
-            let count_deltas = calculate_net_item_delta(packet, &window, cursor_item);
+
             ensure!(
-                count_deltas == 0,
-                "invalid item delta: expected 0, got {}",
-                count_deltas
+                packet.carried_item.item == collect.cursor.item
+                    && packet.carried_item.count == collect.cursor.count,
+                "double click carried item {:?}, expected {:?}",
+                packet.carried_item,
+                collect.cursor
             );
         }
     }
@@ -495,6 +870,391 @@ pub(super) fn validate_click_slot_packet(
     Ok((new_cursor_stack, new_slot_changes))
 }
 
+/// A validated, not-yet-applied inventory mutation: the `(cursor, slot
+/// changes)` pair [`validate_click_slot_packet`] returns, bundled so several
+/// pending clicks from the same tick can be merged and then applied
+/// atomically with [`InventoryTransaction::commit`].
+#[derive(Debug, Clone)]
+pub(super) struct InventoryTransaction {
+    cursor: ItemStack,
+    slot_changes: Vec<SlotChange>,
+}
+
+impl InventoryTransaction {
+    pub(super) fn new(cursor: ItemStack, slot_changes: Vec<SlotChange>) -> Self {
+        Self {
+            cursor,
+            slot_changes,
+        }
+    }
+
+    /// Folds a later click's transaction into this one.
+    ///
+    /// `other` must have been validated against the state this transaction
+    /// would leave the window in (i.e. transactions are merged in
+    /// validation order). Fails if both transactions touch the same slot
+    /// with different end states, since that means `other` was validated
+    /// against a slot value this transaction is about to change out from
+    /// under it.
+    pub(super) fn merge(mut self, other: InventoryTransaction) -> anyhow::Result<Self> {
+        for change in &other.slot_changes {
+            if let Some(existing) = self.slot_changes.iter().find(|s| s.idx == change.idx) {
+                ensure!(
+                    existing.stack.item == change.stack.item
+                        && existing.stack.count == change.stack.count,
+                    "conflicting slot changes for slot {} in the same tick",
+                    change.idx
+                );
+            } else {
+                self.slot_changes.push(change.clone());
+            }
+        }
+
+        self.cursor = other.cursor;
+
+        Ok(self)
+    }
+
+    /// Applies every change in this transaction to `player_inventory`,
+    /// `open_inventory`, and `cursor_item` and reports what moved, or
+    /// leaves all three untouched and returns an error.
+    ///
+    /// The slot bounds check below is the only way this can currently fail;
+    /// every other invariant was already established by
+    /// `validate_click_slot_packet` before the transaction was built.
+    pub(super) fn commit(
+        self,
+        player_inventory: &mut Inventory,
+        mut open_inventory: Option<&mut Inventory>,
+        cursor_item: &mut CursorItem,
+    ) -> anyhow::Result<InventoryChange> {
+        let max_slot = if let Some(open_inv) = open_inventory.as_deref() {
+            PlayerInventory::MAIN_SIZE + open_inv.slot_count()
+        } else {
+            player_inventory.slot_count()
+        };
+
+        ensure!(
+            self.slot_changes
+                .iter()
+                .all(|s| (0..=max_slot).contains(&(s.idx as u16))),
+            "transaction touches a slot outside the window"
+        );
+
+        let mut window = InventoryWindowMut {
+            player_inventory,
+            open_inventory: open_inventory.as_deref_mut(),
+        };
+
+        let mut slots = Vec::with_capacity(self.slot_changes.len());
+        for change in &self.slot_changes {
+            let slot = window.slot_mut(change.idx as u16);
+            let previous = slot.clone();
+            *slot = change.stack.clone();
+
+            slots.push(SlotDelta {
+                idx: change.idx as u16,
+                previous,
+                new: change.stack.clone(),
+            });
+        }
+
+        let previous_cursor = cursor_item.0.clone();
+        cursor_item.0 = self.cursor.clone();
+
+        Ok(InventoryChange {
+            slots,
+            previous_cursor,
+            new_cursor: self.cursor,
+        })
+    }
+}
+
+/// Describes exactly what an [`InventoryTransaction::commit`] changed, for
+/// persistence, undo, or diffing downstream.
+#[derive(Debug, Clone)]
+pub(super) struct InventoryChange {
+    pub slots: Vec<SlotDelta>,
+    pub previous_cursor: ItemStack,
+    pub new_cursor: ItemStack,
+}
+
+/// One slot's state before and after a committed transaction.
+#[derive(Debug, Clone)]
+pub(super) struct SlotDelta {
+    pub idx: u16,
+    pub previous: ItemStack,
+    pub new: ItemStack,
+}
+
+/// One slot drained by [`simulate_double_click_collect`].
+struct DrainedSlot {
+    idx: u16,
+    /// The slot's item after the collect, i.e. with the collected amount
+    /// subtracted (possibly empty).
+    remaining: ItemStack,
+}
+
+/// The result of simulating a double click's "collect to cursor".
+struct DoubleClickCollect {
+    drained: Vec<DrainedSlot>,
+    /// The cursor item after the collect.
+    cursor: ItemStack,
+}
+
+/// Simulates Minecraft's double-click "collect to cursor": starting from
+/// `cursor_item` (empty, or already holding `target_item`), walk the
+/// window in increasing slot order and pull `min(max_stack - held, count)`
+/// out of every slot holding `target_item` until the cursor is full or no
+/// matching slots remain.
+///
+/// Item kind is the only thing compared here; two stacks that share an item
+/// but differ in components should not collect together, but that needs a
+/// component-aware equality check this crate doesn't have yet (see the
+/// `should_swap` TODO in `validate_click_slot_semantics`).
+fn simulate_double_click_collect(
+    window: &InventoryWindow,
+    cursor_item: &CursorItem,
+    target_item: ItemKind,
+    max_slot: u16,
+) -> DoubleClickCollect {
+    let max_stack = target_item.max_stack();
+
+    let mut held = cursor_item.count;
+    let mut drained = Vec::new();
+
+    if held < max_stack {
+        for idx in 0..=max_slot {
+            if held >= max_stack {
+                break;
+            }
+
+            let slot = window.slot(idx);
+            if slot.is_empty() || slot.item != target_item {
+                continue;
+            }
+
+            let take = (max_stack - held).min(slot.count);
+            if take == 0 {
+                continue;
+            }
+
+            held += take;
+            drained.push(DrainedSlot {
+                idx,
+                remaining: slot.clone().with_count(slot.count - take),
+            });
+        }
+    }
+
+    // An empty cursor takes its item (and components) from the first slot
+    // drained, not from the empty cursor stack.
+    let cursor = if cursor_item.is_empty() {
+        drained
+            .first()
+            .map_or(ItemStack::EMPTY, |d| window.slot(d.idx).clone().with_count(held))
+    } else {
+        cursor_item.0.clone().with_count(held)
+    };
+
+    DoubleClickCollect { drained, cursor }
+}
+
+/// Tracks an in-progress [`ClickMode::Drag`] gesture across its
+/// start/add-slot/end packet sequence. See [`validate_click_slot_packet`]'s
+/// doc comment for the lifetime contract.
+#[derive(Default, Debug)]
+pub(super) struct DragSession {
+    active: Option<ActiveDrag>,
+}
+
+#[derive(Debug)]
+struct ActiveDrag {
+    kind: DragKind,
+    painted: Vec<u16>,
+}
+
+/// The paint mode selected by a drag button's high bits (`button / 4`); the
+/// low bits (`button % 4`) select the start/add-slot/end stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DragKind {
+    /// `0/1/2`: split the cursor evenly across painted slots.
+    EvenSplit,
+    /// `4/5/6`: put exactly one item in each painted slot.
+    OnePerSlot,
+    /// `8/9/10`: put a full stack in each painted slot without touching the
+    /// cursor. Only legal in creative mode, which this validator doesn't
+    /// have visibility into, so that restriction isn't enforced here.
+    CreativeClone,
+}
+
+/// One slot painted by a drag-click gesture, with the stack it should hold
+/// once the drag's end event is processed.
+struct PaintedSlot {
+    idx: u16,
+    new_stack: ItemStack,
+}
+
+/// The result of simulating a drag-click's distribution across its painted
+/// slots.
+struct DragDistribution {
+    painted: Vec<PaintedSlot>,
+    /// The cursor item after the distribution.
+    cursor: ItemStack,
+}
+
+/// Simulates the item distribution a drag-click's end event should produce:
+/// `kind` decides whether each of `painted` slots receives an even share of
+/// the cursor (remainder returning to the cursor), exactly one item, or a
+/// full stack (creative clone, which leaves the cursor untouched).
+fn simulate_drag_distribution(
+    window: &InventoryWindow,
+    cursor_item: &CursorItem,
+    kind: DragKind,
+    painted: &[u16],
+) -> anyhow::Result<DragDistribution> {
+    ensure!(!painted.is_empty(), "drag end with no painted slots");
+    ensure!(!cursor_item.is_empty(), "drag end with an empty cursor");
+
+    let item = cursor_item.item;
+    let total = cursor_item.0.count;
+    let n = painted.len() as u8;
+
+    let (per_slot, cursor_count) = match kind {
+        DragKind::EvenSplit => (total / n, total % n),
+        DragKind::OnePerSlot => {
+            ensure!(
+                total >= n,
+                "not enough items in the cursor for a one-per-slot drag"
+            );
+            (1, total - n)
+        }
+        DragKind::CreativeClone => (item.max_stack(), total),
+    };
+
+    let mut result = Vec::with_capacity(painted.len());
+    for &idx in painted {
+        let old_slot = window.slot(idx);
+        ensure!(
+            old_slot.is_empty() || old_slot.item == item,
+            "drag painted slot {} holds a different item",
+            idx
+        );
+
+        let old_count = if old_slot.is_empty() { 0 } else { old_slot.count };
+        let new_count = match kind {
+            DragKind::CreativeClone => per_slot,
+            _ => old_count.saturating_add(per_slot).min(item.max_stack()),
+        };
+
+        result.push(PaintedSlot {
+            idx,
+            new_stack: cursor_item.0.clone().with_count(new_count),
+        });
+    }
+
+    Ok(DragDistribution {
+        painted: result,
+        cursor: cursor_item.0.clone().with_count(cursor_count),
+    })
+}
+
+/// A slot's role within a window, used to decide where a shift-click is
+/// legally allowed to route an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotRole {
+    CraftingOutput,
+    CraftingInput,
+    Head,
+    Chest,
+    Legs,
+    Feet,
+    Offhand,
+    Main,
+    Hotbar,
+    /// A slot belonging to whatever non-player container is open.
+    Container,
+}
+
+/// How many hotbar slots a player has. Fixed by the vanilla protocol.
+const PLAYER_HOTBAR_SIZE: u16 = 9;
+
+/// Classifies `idx` by its role in `window`.
+///
+/// When no container is open, this assumes vanilla's fixed player-screen
+/// layout: slot `0` is the crafting output, `1..=4` the 2x2 crafting grid,
+/// `5..=8` the armor slots (head, chest, legs, feet, in that order), then
+/// main inventory, then the hotbar, then the offhand — ending at
+/// `PlayerInventory::MAIN_SIZE` main+hotbar slots plus one offhand slot.
+/// `PlayerInventory`'s real layout isn't visible in this checkout, so this
+/// is an assumption rather than something read off it.
+///
+/// When a container is open, `window`'s own slots are numbered first
+/// (indices `0..open_inventory.slot_count()`), followed by the player's
+/// main inventory then hotbar — matching the "when the window is split, we
+/// can only access the main slots of player's inventory" comment on
+/// `max_slot`'s computation above.
+fn slot_role(window: &InventoryWindow, idx: u16) -> SlotRole {
+    match window.open_inventory {
+        None => {
+            let main_hotbar_size = PlayerInventory::MAIN_SIZE;
+            let main_size = main_hotbar_size - PLAYER_HOTBAR_SIZE;
+            let hotbar_start = 9 + main_size;
+            let offhand = hotbar_start + PLAYER_HOTBAR_SIZE;
+
+            match idx {
+                0 => SlotRole::CraftingOutput,
+                1..=4 => SlotRole::CraftingInput,
+                5 => SlotRole::Head,
+                6 => SlotRole::Chest,
+                7 => SlotRole::Legs,
+                8 => SlotRole::Feet,
+                i if i < hotbar_start => SlotRole::Main,
+                i if i < offhand => SlotRole::Hotbar,
+                _ => SlotRole::Offhand,
+            }
+        }
+        Some(open_inv) => {
+            let container_size = open_inv.slot_count();
+            if idx < container_size {
+                SlotRole::Container
+            } else if idx - container_size < PlayerInventory::MAIN_SIZE - PLAYER_HOTBAR_SIZE {
+                SlotRole::Main
+            } else {
+                SlotRole::Hotbar
+            }
+        }
+    }
+}
+
+/// The slot role a shift-click should prefer for `item`, when it has a
+/// single well-defined best destination (armor pieces to their matching
+/// armor slot, shields to the offhand).
+///
+/// `ItemKind` carries no equipment-slot accessor in this checkout (just
+/// `max_stack()`), so this hardcodes the vanilla armor/shield item kinds by
+/// name instead of deriving the slot from registry metadata — variant names
+/// follow the same `UpperCamelCase`-of-the-vanilla-id convention
+/// `kanden_generated`'s `EntityKind` generator uses. Anything not listed
+/// here (tools, blocks, crafting-output items, ...) falls back to the
+/// generic main/hotbar/container routing below.
+fn preferred_slot_role(item: ItemKind) -> Option<SlotRole> {
+    use ItemKind::*;
+
+    match item {
+        LeatherHelmet | ChainmailHelmet | IronHelmet | GoldenHelmet | DiamondHelmet
+        | NetheriteHelmet | TurtleHelmet => Some(SlotRole::Head),
+        LeatherChestplate | ChainmailChestplate | IronChestplate | GoldenChestplate
+        | DiamondChestplate | NetheriteChestplate | Elytra => Some(SlotRole::Chest),
+        LeatherLeggings | ChainmailLeggings | IronLeggings | GoldenLeggings
+        | DiamondLeggings | NetheriteLeggings => Some(SlotRole::Legs),
+        LeatherBoots | ChainmailBoots | IronBoots | GoldenBoots | DiamondBoots
+        | NetheriteBoots => Some(SlotRole::Feet),
+        Shield => Some(SlotRole::Offhand),
+        _ => None,
+    }
+}
+
 /// Calculate the total difference in item counts if the changes in this packet
 /// were to be applied.
 ///
@@ -528,3 +1288,47 @@ fn calculate_net_item_delta(
 
     net_item_delta
 }
+
+// `simulate_drag_distribution`, `simulate_double_click_collect`, and
+// `InventoryTransaction::merge`/`commit` all take `Inventory`, `InventoryWindow`,
+// `CursorItem`, `ItemStack`, or `SlotChange` by value or reference, and none of
+// those types has a defining source file in this checkout (no `lib.rs` in
+// this crate, and no `item`/`container_click_c2s` module in `kanden_protocol`
+// either) -- there's no constructor or field layout to build a test fixture
+// against. `DesyncTracker` is the one piece of logic in this file that
+// doesn't touch any of them, so it's the one covered below.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desync() -> ClickValidationError {
+        ClickValidationError::RecoverableDesync(anyhow::anyhow!("test desync"))
+    }
+
+    fn violation() -> ClickValidationError {
+        ClickValidationError::ProtocolViolation(anyhow::anyhow!("test violation"))
+    }
+
+    #[test]
+    fn protocol_violation_always_kicks() {
+        let mut tracker = DesyncTracker::new(8);
+        assert_eq!(tracker.record(&violation()), ClickRecovery::Kick);
+    }
+
+    #[test]
+    fn desyncs_resync_until_threshold_then_kick() {
+        let mut tracker = DesyncTracker::new(2);
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Resync);
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Resync);
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Kick);
+    }
+
+    #[test]
+    fn reset_clears_the_desync_count() {
+        let mut tracker = DesyncTracker::new(1);
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Resync);
+        tracker.reset();
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Resync);
+        assert_eq!(tracker.record(&desync()), ClickRecovery::Kick);
+    }
+}
@@ -0,0 +1,170 @@
+//! Ordered packet-ID assignment.
+//!
+//! Packet IDs are derived from registration order within a `(PacketState,
+//! PacketDirection)` pair rather than hand-maintained per struct: a source
+//! file only has to declare the state and direction it belongs in (as
+//! `ShowDialogS2c` already does with
+//! `#[packet(state = PacketState::Configuration)]`), and its numeric ID falls
+//! out of where it sits in the ordered list below. The direction is part of
+//! the key because the protocol numbers serverbound and clientbound packets
+//! independently — the same state can (and does) have a clientbound packet
+//! and a serverbound packet both claiming index `0`, and those must not
+//! collide into a single counter. `tools/packet_inspector/src/registry.rs`
+//! keys its own packet names by `(state, PacketDirection, id)` for the same
+//! reason.
+//!
+//! NOTE: the `Packet` derive (in `kanden_protocol_macros`, not part of this
+//! checkout) is what would read a registration's position here and use it as
+//! the encoded/decoded ID instead of a literal. [`assert_packet_count`] and
+//! [`assert_packet_order`] are usable today to pin a state's layout in
+//! tests, so accidental reordering across Minecraft version bumps is caught
+//! regardless of whether the derive has picked this up yet.
+
+use crate::PacketState;
+
+/// Which direction a packet travels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketDirection {
+    /// Client to server.
+    Serverbound,
+    /// Server to client.
+    Clientbound,
+}
+
+/// A single entry in the ordered packet list for a [`PacketState`].
+#[derive(Clone, Copy, Debug)]
+pub struct PacketRegistration {
+    /// The Rust type name of the packet struct, e.g. `"ShowDialogS2c"`.
+    pub name: &'static str,
+    pub state: PacketState,
+    pub direction: PacketDirection,
+}
+
+impl PacketRegistration {
+    pub const fn new(name: &'static str, state: PacketState, direction: PacketDirection) -> Self {
+        Self {
+            name,
+            state,
+            direction,
+        }
+    }
+}
+
+/// Returns the packets registered for `(state, direction)`, in registration
+/// order, along with their assigned ID (their index within that
+/// state/direction pair).
+pub fn packets_in_state(
+    registrations: &'static [PacketRegistration],
+    state: PacketState,
+    direction: PacketDirection,
+) -> impl Iterator<Item = (i32, &'static PacketRegistration)> {
+    registrations
+        .iter()
+        .filter(move |reg| reg.state == state && reg.direction == direction)
+        .enumerate()
+        .map(|(id, reg)| (id as i32, reg))
+}
+
+/// Asserts that exactly `expected` packets are registered for
+/// `(state, direction)`.
+///
+/// Intended for use in tests that pin a state's packet count so adding or
+/// removing a packet without updating the test is caught in review.
+pub fn assert_packet_count(
+    registrations: &'static [PacketRegistration],
+    state: PacketState,
+    direction: PacketDirection,
+    expected: usize,
+) {
+    let actual = packets_in_state(registrations, state, direction).count();
+
+    assert_eq!(
+        actual, expected,
+        "expected {expected} packets registered for {state:?}/{direction:?}, found {actual}"
+    );
+}
+
+/// Asserts that the packets registered for `(state, direction)` appear in
+/// exactly `expected` order (by type name).
+///
+/// Intended for use in tests that pin a state's packet layout so reordering
+/// registrations (which would silently renumber every packet after the
+/// moved one) is caught in review.
+pub fn assert_packet_order(
+    registrations: &'static [PacketRegistration],
+    state: PacketState,
+    direction: PacketDirection,
+    expected: &[&str],
+) {
+    let actual: Vec<&str> = packets_in_state(registrations, state, direction)
+        .map(|(_, reg)| reg.name)
+        .collect();
+
+    assert_eq!(
+        actual, expected,
+        "packet order for {state:?}/{direction:?} does not match the pinned layout"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRATIONS: &[PacketRegistration] = &[
+        PacketRegistration::new(
+            "CookieRequestS2c",
+            PacketState::Configuration,
+            PacketDirection::Clientbound,
+        ),
+        PacketRegistration::new(
+            "ClientInformationC2s",
+            PacketState::Configuration,
+            PacketDirection::Serverbound,
+        ),
+        PacketRegistration::new(
+            "CookieResponseC2s",
+            PacketState::Configuration,
+            PacketDirection::Serverbound,
+        ),
+        PacketRegistration::new(
+            "DisconnectS2c",
+            PacketState::Configuration,
+            PacketDirection::Clientbound,
+        ),
+    ];
+
+    #[test]
+    fn counts_are_independent_per_direction() {
+        assert_packet_count(
+            REGISTRATIONS,
+            PacketState::Configuration,
+            PacketDirection::Clientbound,
+            2,
+        );
+        assert_packet_count(
+            REGISTRATIONS,
+            PacketState::Configuration,
+            PacketDirection::Serverbound,
+            2,
+        );
+    }
+
+    #[test]
+    fn ids_do_not_collide_across_direction() {
+        // Both directions' first packet gets ID 0 -- that's fine, since the
+        // two directions are numbered independently and never compared
+        // against each other.
+        assert_packet_order(
+            REGISTRATIONS,
+            PacketState::Configuration,
+            PacketDirection::Clientbound,
+            &["CookieRequestS2c", "DisconnectS2c"],
+        );
+        assert_packet_order(
+            REGISTRATIONS,
+            PacketState::Configuration,
+            PacketDirection::Serverbound,
+            &["ClientInformationC2s", "CookieResponseC2s"],
+        );
+    }
+}
@@ -4,9 +4,14 @@ use kanden_ident::Ident;
 
 use crate::{Decode, Encode, Packet, PacketState};
 
+/// Clears a dialog on the client.
+///
+/// `target` names the `id` the dialog was shown with (see `Dialog`'s
+/// `DialogBase`), so a specific, possibly-stale dialog can be dismissed
+/// without affecting one opened after it; `None` clears whatever dialog is
+/// currently open.
 #[derive(Clone, Debug, Encode, Decode, Packet)]
 #[packet(state = PacketState::Configuration)]
-/// Clears dialog on client
-pub struct ClearDialogS2c {
-    // TODO
+pub struct ClearDialogS2c<'a> {
+    pub target: Option<Ident<Cow<'a, str>>>,
 }
@@ -1,12 +1,237 @@
-use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Write;
 
 use kanden_ident::Ident;
+use kanden_nbt::serde::ser::CompoundSerializer;
+use kanden_nbt::Compound;
+use kanden_text::Text;
+use serde::{Deserialize, Serialize};
 
 use crate::{Decode, Encode, Packet, PacketState};
 
-#[derive(Clone, Debug, Encode, Decode, Packet)]
+/// Shows a dialog on the client: a native form (notice, confirmation,
+/// multi-action menu, or input fields) rendered without a resource pack.
+///
+/// The whole [`Dialog`] is encoded as a single NBT `Compound`, the same way
+/// `EnvironmentAttribute` round-trips through `Compound` in `kanden_registry`.
+///
+/// Clicking a [`DialogAction`] comes back as a `CustomClickActionC2s`
+/// carrying that action's `click_id`.
+#[derive(Clone, Debug, Packet)]
 #[packet(state = PacketState::Configuration)]
-/// Shows a dialog on client
 pub struct ShowDialogS2c {
-    // TODO
+    pub dialog: Dialog,
+}
+
+impl Encode for ShowDialogS2c {
+    fn encode(&self, w: impl Write) -> anyhow::Result<()> {
+        let compound = self
+            .dialog
+            .serialize(CompoundSerializer)
+            .map_err(|e| anyhow::anyhow!("failed to serialize Dialog: {e}"))?;
+        compound.encode(w)
+    }
+}
+
+impl<'a> Decode<'a> for ShowDialogS2c {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let compound = Compound::decode(r)?;
+        let dialog = Dialog::deserialize(compound)
+            .map_err(|e| anyhow::anyhow!("failed to deserialize Dialog: {e}"))?;
+        Ok(Self { dialog })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Dialog {
+    Notice(NoticeDialog),
+    Confirmation(ConfirmationDialog),
+    MultiAction(MultiActionDialog),
+    Input(InputDialog),
+}
+
+impl Dialog {
+    pub fn notice(id: impl Into<Ident<String>>, title: Text, action: DialogAction) -> Self {
+        Self::Notice(NoticeDialog {
+            base: DialogBase::new(id, title),
+            action,
+        })
+    }
+
+    pub fn confirmation(
+        id: impl Into<Ident<String>>,
+        title: Text,
+        yes: DialogAction,
+        no: DialogAction,
+    ) -> Self {
+        Self::Confirmation(ConfirmationDialog {
+            base: DialogBase::new(id, title),
+            yes,
+            no,
+        })
+    }
+
+    pub fn multi_action(
+        id: impl Into<Ident<String>>,
+        title: Text,
+        actions: Vec<DialogAction>,
+        columns: u8,
+    ) -> Self {
+        Self::MultiAction(MultiActionDialog {
+            base: DialogBase::new(id, title),
+            actions,
+            exit_action: None,
+            columns,
+        })
+    }
+
+    pub fn input(
+        id: impl Into<Ident<String>>,
+        title: Text,
+        fields: Vec<InputField>,
+        action: DialogAction,
+    ) -> Self {
+        Self::Input(InputDialog {
+            base: DialogBase::new(id, title),
+            fields,
+            action,
+        })
+    }
+}
+
+/// Fields shared by every [`Dialog`] variant.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DialogBase {
+    pub id: Ident<String>,
+    pub title: Text,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Text>,
+}
+
+impl DialogBase {
+    fn new(id: impl Into<Ident<String>>, title: Text) -> Self {
+        Self {
+            id: id.into(),
+            title,
+            body: None,
+        }
+    }
+
+    pub fn with_body(mut self, body: Text) -> Self {
+        self.body = Some(body);
+        self
+    }
+}
+
+/// A single acknowledgment, with no other fields to submit.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct NoticeDialog {
+    #[serde(flatten)]
+    pub base: DialogBase,
+    pub action: DialogAction,
+}
+
+/// A choice between two actions.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ConfirmationDialog {
+    #[serde(flatten)]
+    pub base: DialogBase,
+    pub yes: DialogAction,
+    pub no: DialogAction,
+}
+
+/// A menu of independent actions, laid out in `columns` columns.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct MultiActionDialog {
+    #[serde(flatten)]
+    pub base: DialogBase,
+    pub actions: Vec<DialogAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_action: Option<DialogAction>,
+    pub columns: u8,
+}
+
+/// A form made of [`InputField`]s, submitted together via `action`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct InputDialog {
+    #[serde(flatten)]
+    pub base: DialogBase,
+    pub fields: Vec<InputField>,
+    pub action: DialogAction,
+}
+
+/// A clickable button. Clicking it sends a `CustomClickActionC2s` whose `id`
+/// equals `click_id`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DialogAction {
+    pub label: Text,
+    pub click_id: Ident<String>,
+}
+
+/// One field of an [`InputDialog`], keyed by `key` in the submitted payload.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum InputField {
+    Text {
+        key: Ident<String>,
+        label: Text,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        initial: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_length: Option<u32>,
+    },
+    Boolean {
+        key: Ident<String>,
+        label: Text,
+        #[serde(default)]
+        initial: bool,
+    },
+    NumberRange {
+        key: Ident<String>,
+        label: Text,
+        start: f32,
+        end: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        initial: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        step: Option<f32>,
+    },
+    SingleOption {
+        key: Ident<String>,
+        label: Text,
+        options: Vec<DialogOption>,
+    },
+}
+
+impl InputField {
+    pub fn key(&self) -> &Ident<String> {
+        match self {
+            InputField::Text { key, .. }
+            | InputField::Boolean { key, .. }
+            | InputField::NumberRange { key, .. }
+            | InputField::SingleOption { key, .. } => key,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct DialogOption {
+    pub id: String,
+    pub display: Text,
+}
+
+/// A value submitted for one [`InputField`] of an [`InputDialog`].
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubmittedValue {
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+}
+
+/// Decodes an [`InputDialog`] submission's raw NBT payload into field values,
+/// keyed by each field's `key`.
+pub fn parse_submission(payload: Compound) -> anyhow::Result<HashMap<String, SubmittedValue>> {
+    HashMap::deserialize(payload).map_err(|e| anyhow::anyhow!("failed to parse dialog submission: {e}"))
 }
@@ -1,3 +1,4 @@
+use kanden_generated::entity::EntityKind;
 use kanden_math::{DVec3, I8Vec3};
 use uuid::Uuid;
 
@@ -10,7 +11,7 @@ use crate::{ByteAngle, Decode, Encode, Packet, VarInt, Velocity};
 pub struct AddEntityS2c {
     pub entity_id: VarInt,
     pub object_uuid: Uuid,
-    pub kind: VarInt, // TODO: EntityKind in kanden_generated?
+    pub kind: EntityKind,
     pub position: DVec3,
     pub velocity: Velocity,
     pub pitch: ByteAngle,
@@ -0,0 +1,290 @@
+use std::borrow::Cow;
+use std::io::Write;
+
+use crate::{Decode, Encode, Packet, VarInt};
+
+/// Sent by the server to declare the command tree, driving tab completion and
+/// command highlighting on the client.
+///
+/// Every client gets its own copy of this packet, pruned to the nodes its
+/// [`CommandScopes`] grant — see [`build_filtered_tree`].
+///
+/// wiki: [Commands](https://wiki.vg/Protocol#Commands)
+///
+/// [`CommandScopes`]: kanden_command::scopes::CommandScopes
+/// [`build_filtered_tree`]: kanden_command::sync::build_filtered_tree
+#[derive(Clone, PartialEq, Debug, Packet)]
+pub struct CommandsS2c<'a> {
+    pub nodes: Vec<CommandNode<'a>>,
+    pub root_index: VarInt,
+}
+
+impl<'a> Encode for CommandsS2c<'a> {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        VarInt(self.nodes.len() as i32).encode(&mut w)?;
+        for node in &self.nodes {
+            node.encode(&mut w)?;
+        }
+        self.root_index.encode(&mut w)?;
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for CommandsS2c<'a> {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let count = VarInt::decode(r)?.0 as usize;
+        let mut nodes = Vec::with_capacity(count);
+        for _ in 0..count {
+            nodes.push(CommandNode::decode(r)?);
+        }
+        let root_index = VarInt::decode(r)?;
+        Ok(Self { nodes, root_index })
+    }
+}
+
+/// One node of a [`CommandsS2c`] tree.
+///
+/// The protocol calls `redirect_node` a "redirect": it aliases this node onto
+/// another node's children instead of duplicating them (e.g. a short alias
+/// pointing at the real command). [`CommandNode`](kanden_command::dispatcher::CommandNode)
+/// has no alias concept of its own yet -- note this is unrelated to
+/// [`CommandScopeRegistry::link`](kanden_command::scopes::CommandScopeRegistry::link),
+/// which links *scopes* (permission grants) to each other, not command
+/// nodes -- so [`build_filtered_tree`] always sends `None` here for now.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CommandNode<'a> {
+    pub children: Vec<VarInt>,
+    pub redirect_node: Option<VarInt>,
+    pub executable: bool,
+    pub data: CommandNodeData<'a>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum CommandNodeData<'a> {
+    Root,
+    Literal {
+        name: Cow<'a, str>,
+    },
+    Argument {
+        name: Cow<'a, str>,
+        parser: CommandParser,
+    },
+}
+
+const FLAG_NODE_TYPE_MASK: u8 = 0x03;
+const FLAG_NODE_TYPE_ROOT: u8 = 0;
+const FLAG_NODE_TYPE_LITERAL: u8 = 1;
+const FLAG_NODE_TYPE_ARGUMENT: u8 = 2;
+const FLAG_EXECUTABLE: u8 = 0x04;
+const FLAG_HAS_REDIRECT: u8 = 0x08;
+
+impl<'a> Encode for CommandNode<'a> {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        let mut flags = match &self.data {
+            CommandNodeData::Root => FLAG_NODE_TYPE_ROOT,
+            CommandNodeData::Literal { .. } => FLAG_NODE_TYPE_LITERAL,
+            CommandNodeData::Argument { .. } => FLAG_NODE_TYPE_ARGUMENT,
+        };
+        if self.executable {
+            flags |= FLAG_EXECUTABLE;
+        }
+        if self.redirect_node.is_some() {
+            flags |= FLAG_HAS_REDIRECT;
+        }
+        flags.encode(&mut w)?;
+
+        VarInt(self.children.len() as i32).encode(&mut w)?;
+        for child in &self.children {
+            child.encode(&mut w)?;
+        }
+
+        if let Some(redirect_node) = &self.redirect_node {
+            redirect_node.encode(&mut w)?;
+        }
+
+        match &self.data {
+            CommandNodeData::Root => {}
+            CommandNodeData::Literal { name } => {
+                name.as_ref().to_owned().encode(&mut w)?;
+            }
+            CommandNodeData::Argument { name, parser } => {
+                name.as_ref().to_owned().encode(&mut w)?;
+                parser.encode(&mut w)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Decode<'a> for CommandNode<'a> {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let flags = u8::decode(r)?;
+        let executable = flags & FLAG_EXECUTABLE != 0;
+        let has_redirect = flags & FLAG_HAS_REDIRECT != 0;
+
+        let child_count = VarInt::decode(r)?.0 as usize;
+        let mut children = Vec::with_capacity(child_count);
+        for _ in 0..child_count {
+            children.push(VarInt::decode(r)?);
+        }
+
+        let redirect_node = if has_redirect {
+            Some(VarInt::decode(r)?)
+        } else {
+            None
+        };
+
+        let data = match flags & FLAG_NODE_TYPE_MASK {
+            FLAG_NODE_TYPE_LITERAL => CommandNodeData::Literal {
+                name: Cow::Borrowed(<&'a str>::decode(r)?),
+            },
+            FLAG_NODE_TYPE_ARGUMENT => CommandNodeData::Argument {
+                name: Cow::Borrowed(<&'a str>::decode(r)?),
+                parser: CommandParser::decode(r)?,
+            },
+            _ => CommandNodeData::Root,
+        };
+
+        Ok(Self {
+            children,
+            redirect_node,
+            executable,
+            data,
+        })
+    }
+}
+
+/// The subset of vanilla Brigadier argument parsers our [`ArgumentKind`]s map
+/// onto, along with each parser's properties payload.
+///
+/// Suggestion providers aren't encoded here -- the client falls back to
+/// accepting any value of the right shape for those, which is enough for
+/// tab-completion and highlighting to work.
+///
+/// [`ArgumentKind`]: kanden_command::dispatcher::ArgumentKind
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CommandParser {
+    Integer { min: Option<i32>, max: Option<i32> },
+    Float { min: Option<f32>, max: Option<f32> },
+    String(StringParserKind),
+    Entity { single: bool, players_only: bool },
+}
+
+/// `brigadier:string`'s `SINGLE_WORD`/`QUOTABLE_PHRASE`/`GREEDY_PHRASE`
+/// behavior selector.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode, Decode)]
+pub enum StringParserKind {
+    SingleWord,
+    QuotablePhrase,
+    GreedyPhrase,
+}
+
+const PARSER_ID_INTEGER: i32 = 0;
+const PARSER_ID_FLOAT: i32 = 1;
+const PARSER_ID_STRING: i32 = 2;
+const PARSER_ID_ENTITY: i32 = 3;
+
+const NUMBER_FLAG_MIN: u8 = 0x01;
+const NUMBER_FLAG_MAX: u8 = 0x02;
+
+const ENTITY_FLAG_SINGLE: u8 = 0x01;
+const ENTITY_FLAG_PLAYERS_ONLY: u8 = 0x02;
+
+impl Encode for CommandParser {
+    fn encode(&self, mut w: impl Write) -> anyhow::Result<()> {
+        match self {
+            CommandParser::Integer { min, max } => {
+                VarInt(PARSER_ID_INTEGER).encode(&mut w)?;
+                encode_number_properties(*min, *max, &mut w)?;
+            }
+            CommandParser::Float { min, max } => {
+                VarInt(PARSER_ID_FLOAT).encode(&mut w)?;
+                encode_number_properties(*min, *max, &mut w)?;
+            }
+            CommandParser::String(kind) => {
+                VarInt(PARSER_ID_STRING).encode(&mut w)?;
+                kind.encode(&mut w)?;
+            }
+            CommandParser::Entity {
+                single,
+                players_only,
+            } => {
+                VarInt(PARSER_ID_ENTITY).encode(&mut w)?;
+                let mut flags = 0u8;
+                if *single {
+                    flags |= ENTITY_FLAG_SINGLE;
+                }
+                if *players_only {
+                    flags |= ENTITY_FLAG_PLAYERS_ONLY;
+                }
+                flags.encode(&mut w)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn encode_number_properties<T: Encode>(
+    min: Option<T>,
+    max: Option<T>,
+    mut w: impl Write,
+) -> anyhow::Result<()> {
+    let mut flags = 0u8;
+    if min.is_some() {
+        flags |= NUMBER_FLAG_MIN;
+    }
+    if max.is_some() {
+        flags |= NUMBER_FLAG_MAX;
+    }
+    flags.encode(&mut w)?;
+    if let Some(min) = min {
+        min.encode(&mut w)?;
+    }
+    if let Some(max) = max {
+        max.encode(&mut w)?;
+    }
+    Ok(())
+}
+
+impl<'a> Decode<'a> for CommandParser {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let id = VarInt::decode(r)?.0;
+        Ok(match id {
+            PARSER_ID_INTEGER => {
+                let (min, max) = decode_number_properties(r)?;
+                CommandParser::Integer { min, max }
+            }
+            PARSER_ID_FLOAT => {
+                let (min, max) = decode_number_properties(r)?;
+                CommandParser::Float { min, max }
+            }
+            PARSER_ID_STRING => CommandParser::String(StringParserKind::decode(r)?),
+            PARSER_ID_ENTITY => {
+                let flags = u8::decode(r)?;
+                CommandParser::Entity {
+                    single: flags & ENTITY_FLAG_SINGLE != 0,
+                    players_only: flags & ENTITY_FLAG_PLAYERS_ONLY != 0,
+                }
+            }
+            _ => anyhow::bail!("invalid command parser id {id}"),
+        })
+    }
+}
+
+fn decode_number_properties<'a, T: Decode<'a>>(
+    r: &mut &'a [u8],
+) -> anyhow::Result<(Option<T>, Option<T>)> {
+    let flags = u8::decode(r)?;
+    let min = if flags & NUMBER_FLAG_MIN != 0 {
+        Some(T::decode(r)?)
+    } else {
+        None
+    };
+    let max = if flags & NUMBER_FLAG_MAX != 0 {
+        Some(T::decode(r)?)
+    } else {
+        None
+    };
+    Ok((min, max))
+}
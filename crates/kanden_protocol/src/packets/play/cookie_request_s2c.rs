@@ -0,0 +1,12 @@
+use std::borrow::Cow;
+
+use kanden_ident::Ident;
+
+use crate::{Decode, Encode, Packet};
+
+/// Asks the client to return the cookie stored under `key`, if it has one,
+/// via [`CookieResponseC2s`](super::CookieResponseC2s).
+#[derive(Clone, Debug, Encode, Decode, Packet)]
+pub struct CookieRequestS2c<'a> {
+    pub key: Ident<Cow<'a, str>>,
+}
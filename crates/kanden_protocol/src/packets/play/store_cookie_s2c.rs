@@ -0,0 +1,17 @@
+use std::borrow::Cow;
+
+use kanden_ident::Ident;
+
+use crate::{Decode, Encode, Packet};
+
+/// Asks the client to store `payload` under `key`, up to 5 KiB per cookie.
+///
+/// The client holds onto the cookie across a [`TransferS2c`](super::TransferS2c)
+/// to a different server, re-sending it on a matching
+/// [`CookieRequestS2c`](super::CookieRequestS2c) — this is the protocol's
+/// mechanism for carrying continuation state through a server hand-off.
+#[derive(Clone, Debug, Encode, Decode, Packet)]
+pub struct StoreCookieS2c<'a> {
+    pub key: Ident<Cow<'a, str>>,
+    pub payload: Cow<'a, [u8]>,
+}
@@ -1,11 +1,11 @@
 use std::borrow::Cow;
 
-use kanden_ident::Ident;
-
 use crate::{Decode, Encode, Packet, VarInt};
 
 #[derive(Clone, Debug, Encode, Decode, Packet)]
 pub struct TransferS2c<'a> {
-    pub host: Ident<Cow<'a, str>>,
+    /// A plain DNS host or IP literal, not a namespaced [`Ident`](kanden_ident::Ident) —
+    /// the client treats this as an address to reconnect to, not a registry key.
+    pub host: Cow<'a, str>,
     pub port: VarInt,
 }
@@ -0,0 +1,91 @@
+//! Support for negotiating and dispatching on the client's protocol version.
+//!
+//! The handshake packet carries the protocol version the client wants to
+//! speak. [`ProtocolVersion`] is that value given a type so it can be
+//! threaded through the codec and used as part of a packet-ID lookup key,
+//! and [`VersionedPacketIds`] is the per-`(PacketState, ProtocolVersion)` ID
+//! map that lookup resolves against.
+//!
+//! NOTE: per-field conditional gating (e.g. a struct declaring
+//! `#[packet(since = ...)]` on a field so it's only present on some
+//! versions) is implemented in the `Packet` derive macro, which lives in
+//! `kanden_protocol_macros` and isn't part of this checkout. The pieces here
+//! are the parts of version dispatch that live in `kanden_protocol` itself.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Decode, Encode, PacketState, VarInt};
+
+/// A negotiated Minecraft protocol version number, as sent in the handshake
+/// packet.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ProtocolVersion(pub i32);
+
+impl ProtocolVersion {
+    pub const fn new(value: i32) -> Self {
+        Self(value)
+    }
+}
+
+impl Encode for ProtocolVersion {
+    fn encode(&self, w: impl std::io::Write) -> anyhow::Result<()> {
+        VarInt(self.0).encode(w)
+    }
+}
+
+impl<'a> Decode<'a> for ProtocolVersion {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        Ok(Self(VarInt::decode(r)?.0))
+    }
+}
+
+/// Maps `(state, id)` to the packet name registered for that slot, per
+/// negotiated protocol version.
+///
+/// This lets a single packet struct keep one Rust name and layout across
+/// versions while the numeric ID it's read from (or written to) on the wire
+/// is resolved per `(PacketState, ProtocolVersion)` pair at the point the
+/// connection's version becomes known, rather than being a single
+/// crate-wide constant.
+#[derive(Default, Debug)]
+pub struct VersionedPacketIds {
+    // Keyed by version first so a lookup for an unlisted version can fall
+    // back to the closest version below it without a second map.
+    versions: BTreeMap<ProtocolVersion, HashMap<(PacketState, i32), &'static str>>,
+}
+
+impl VersionedPacketIds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `packet_name` under `id` for `state`, starting at
+    /// `since` (inclusive) and applying to every later version until a
+    /// newer registration for the same `(state, id)` overrides it.
+    pub fn register(
+        &mut self,
+        since: ProtocolVersion,
+        state: PacketState,
+        id: i32,
+        packet_name: &'static str,
+    ) {
+        self.versions
+            .entry(since)
+            .or_default()
+            .insert((state, id), packet_name);
+    }
+
+    /// Resolves the packet name registered for `(state, id)` as of `version`,
+    /// i.e. the most recent registration at or before `version`.
+    pub fn resolve(
+        &self,
+        version: ProtocolVersion,
+        state: PacketState,
+        id: i32,
+    ) -> Option<&'static str> {
+        self.versions
+            .range(..=version)
+            .rev()
+            .find_map(|(_, ids)| ids.get(&(state, id)).copied())
+    }
+}
@@ -0,0 +1,40 @@
+//! A packet-writing destination that isn't tied to any particular S2C packet
+//! enum.
+//!
+//! Gameplay systems regularly need to send a packet the crate doesn't model
+//! with a dedicated event (or a packet with fields the event API doesn't
+//! expose, like a custom [`ShowDialogS2c`]). [`WritePacket`] is the escape
+//! hatch for that: anything that can accept raw outgoing bytes gets
+//! [`write_packet`](WritePacket::write_packet) for free.
+//!
+//! [`ShowDialogS2c`]: crate::packets::common::ShowDialogS2c
+
+use crate::{Encode, Packet};
+
+pub trait WritePacket {
+    /// Encodes `packet` and writes it to this destination.
+    ///
+    /// Encoding failures are logged rather than propagated: `packet` is
+    /// always encoded into an in-memory buffer first, so a failure here
+    /// means the packet itself is malformed (not an I/O error), and callers
+    /// broadcasting to many viewers shouldn't have to handle that per call.
+    fn write_packet<P>(&mut self, packet: &P)
+    where
+        P: Packet + Encode,
+    {
+        let mut buf = Vec::new();
+
+        match packet.encode(&mut buf) {
+            Ok(()) => self.write_packet_bytes(&buf),
+            Err(e) => {
+                tracing::error!(
+                    "failed to encode packet {}: {e:#}",
+                    std::any::type_name::<P>()
+                );
+            }
+        }
+    }
+
+    /// Writes an already-encoded packet's bytes to this destination.
+    fn write_packet_bytes(&mut self, bytes: &[u8]);
+}
@@ -0,0 +1,193 @@
+//! Contains damage types and the damage type registry. Minecraft's default
+//! damage types are added to the registry by default.
+//!
+//! Beyond the vanilla `minecraft:damage_type` fields, [`DamageType`] carries
+//! two Kanden-specific fields — `knockback_multiplier` and
+//! `invulnerability_ticks` — that `kanden_server`'s combat subsystem reads
+//! to scale knockback and size the post-hit invulnerability window. They're
+//! serialized alongside the vanilla fields, so custom damage types can set
+//! them the same way they set `exhaustion` or `scaling`.
+
+use std::ops::{Deref, DerefMut};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use kanden_ident::{ident, Ident};
+use kanden_nbt::serde::ser::CompoundSerializer;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::codec::RegistryCodec;
+use crate::{Registry, RegistryIdx, RegistrySet};
+
+pub struct DamageTypePlugin;
+
+impl Plugin for DamageTypePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DamageTypeRegistry>()
+            .add_systems(PreStartup, load_default_damage_types)
+            .add_systems(PostUpdate, update_damage_type_registry.before(RegistrySet));
+    }
+}
+
+/// Loads the default damage types from the registry codec.
+fn load_default_damage_types(mut reg: ResMut<DamageTypeRegistry>, codec: Res<RegistryCodec>) {
+    let mut helper = move || -> anyhow::Result<()> {
+        for (name, element) in codec.registry(DamageTypeRegistry::KEY) {
+            let damage_type = DamageType::deserialize(element.clone())?;
+            reg.insert(name.clone(), damage_type);
+        }
+
+        Ok(())
+    };
+
+    if let Err(e) = helper() {
+        error!("failed to load default damage types from registry codec: {e:#}");
+    }
+}
+
+/// Updates the registry codec as the damage type registry is modified by
+/// users.
+fn update_damage_type_registry(reg: Res<DamageTypeRegistry>, mut codec: ResMut<RegistryCodec>) {
+    if reg.is_changed() {
+        let damage_types = codec.registry_mut(DamageTypeRegistry::KEY);
+
+        damage_types.clear();
+
+        damage_types.extend(reg.iter().map(|(_, name, damage_type)| {
+            (
+                name.into(),
+                damage_type
+                    .serialize(CompoundSerializer)
+                    .expect("failed to serialize damage type"),
+            )
+        }));
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct DamageTypeRegistry {
+    reg: Registry<DamageTypeId, DamageType>,
+}
+
+impl DamageTypeRegistry {
+    pub const KEY: Ident<&'static str> = ident!("damage_type");
+
+    /// Looks up the registry index a damage type was inserted under, for use
+    /// in protocol packets that reference damage types by their numeric id
+    /// (e.g. `DamageEventS2c::source_type_id`).
+    pub fn id_of(&self, name: &Ident<String>) -> Option<DamageTypeId> {
+        self.reg
+            .iter()
+            .find_map(|(idx, n, _)| (n == name).then_some(idx))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct DamageTypeId(u16);
+
+impl DamageTypeId {
+    pub fn new(value: u16) -> Self {
+        DamageTypeId(value)
+    }
+
+    pub fn get_value(&self) -> u16 {
+        self.0
+    }
+}
+
+impl RegistryIdx for DamageTypeId {
+    const MAX: usize = u16::MAX as usize;
+
+    fn to_index(self) -> usize {
+        self.0 as usize
+    }
+
+    fn from_index(idx: usize) -> Self {
+        Self(idx as u16)
+    }
+}
+
+impl Deref for DamageTypeRegistry {
+    type Target = Registry<DamageTypeId, DamageType>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.reg
+    }
+}
+
+impl DerefMut for DamageTypeRegistry {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.reg
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DamageType {
+    pub message_id: String,
+    pub scaling: DamageScaling,
+    pub exhaustion: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effects: Option<DamageEffects>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub death_message_type: Option<DeathMessageType>,
+    /// Scales the knockback applied by `ApplyDamage` events of this type.
+    /// Not a vanilla `damage_type` field.
+    #[serde(default = "default_knockback_multiplier")]
+    pub knockback_multiplier: f32,
+    /// How many ticks a target is immune to further hits of this type after
+    /// being struck. Not a vanilla `damage_type` field.
+    #[serde(default = "default_invulnerability_ticks")]
+    pub invulnerability_ticks: u32,
+}
+
+fn default_knockback_multiplier() -> f32 {
+    1.0
+}
+
+fn default_invulnerability_ticks() -> u32 {
+    10
+}
+
+impl Default for DamageType {
+    fn default() -> Self {
+        Self {
+            message_id: String::from("generic"),
+            scaling: DamageScaling::default(),
+            exhaustion: 0.1,
+            effects: None,
+            death_message_type: None,
+            knockback_multiplier: default_knockback_multiplier(),
+            invulnerability_ticks: default_invulnerability_ticks(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageScaling {
+    Never,
+    #[default]
+    WhenCausedByLivingNonPlayer,
+    Always,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DamageEffects {
+    Hurt,
+    Thorns,
+    Drowning,
+    Burning,
+    Poking,
+    Freezing,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathMessageType {
+    Default,
+    FallVariants,
+    IntentionalGameDesign,
+}
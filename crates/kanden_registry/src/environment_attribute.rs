@@ -6,6 +6,7 @@ use kanden_nbt::{compound, serde::ser::CompoundSerializer, Compound};
 use kanden_protocol::Text;
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::keyframe::{Angle, Keyframed};
 use crate::serde::{ARGB, RGB};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -130,43 +131,43 @@ impl Deref for BooleanIntRepr {
 #[serde(tag = "type", content = "data")]
 pub enum EnvironmentAttribute {
     #[serde(rename = "minecraft:visual/fog_color")]
-    FogColor(RGB),
+    FogColor(Keyframed<RGB>),
     #[serde(rename = "minecraft:visual/fog_start_distance")]
-    FogStartDistance(f32),
+    FogStartDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/fog_end_distance")]
-    FogEndDistance(f32),
+    FogEndDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/sky_fog_end_distance")]
-    SkyFogEndDistance(f32),
+    SkyFogEndDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/cloud_fog_end_distance")]
-    CloudFogEndDistance(f32),
+    CloudFogEndDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/water_fog_color")]
-    WaterFogColor(RGB),
+    WaterFogColor(Keyframed<RGB>),
     #[serde(rename = "minecraft:visual/water_fog_start_distance")]
-    WaterFogStartDistance(f32),
+    WaterFogStartDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/water_fog_end_distance")]
-    WaterFogEndDistance(f32),
+    WaterFogEndDistance(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/sky_color")]
-    SkyColor(RGB),
+    SkyColor(Keyframed<RGB>),
     #[serde(rename = "minecraft:visual/sunrise_sunset_color")]
-    SunriseSunsetColor(ARGB),
+    SunriseSunsetColor(Keyframed<ARGB>),
     #[serde(rename = "minecraft:visual/cloud_color")]
-    CloudColor(ARGB),
+    CloudColor(Keyframed<ARGB>),
     #[serde(rename = "minecraft:visual/cloud_height")]
     CloudHeight(f32),
     #[serde(rename = "minecraft:visual/sun_angle")]
-    SunAngle(f32),
+    SunAngle(Keyframed<Angle>),
     #[serde(rename = "minecraft:visual/moon_angle")]
-    MoonAngle(f32),
+    MoonAngle(Keyframed<Angle>),
     #[serde(rename = "minecraft:visual/star_angle")]
-    StarAngle(f32),
+    StarAngle(Keyframed<Angle>),
     #[serde(rename = "minecraft:visual/moon_phase")]
     MoonPhase { keyframes: Vec<MoonPhaseKeyframe> },
     #[serde(rename = "minecraft:visual/star_brightness")]
     StarBrightness(f32),
     #[serde(rename = "minecraft:visual/sky_light_color")]
-    SkyLightColor(RGB),
+    SkyLightColor(Keyframed<RGB>),
     #[serde(rename = "minecraft:visual/sky_light_factor")]
-    SkyLightFactor(f32),
+    SkyLightFactor(Keyframed<f32>),
     #[serde(rename = "minecraft:visual/default_dripstone_particle")]
     DefaultDripstoneParticle(ParticleOptions),
     #[serde(rename = "minecraft:visual/ambient_particles")]
@@ -174,7 +175,7 @@ pub enum EnvironmentAttribute {
     #[serde(rename = "minecraft:audio/background_music")]
     BackgroundMusic(BackgroundMusic),
     #[serde(rename = "minecraft:audio/music_volume")]
-    MusicVolume(f32),
+    MusicVolume(Keyframed<f32>),
     #[serde(rename = "minecraft:audio/ambient_sounds")]
     AmbientSounds(AmbientSounds),
     #[serde(rename = "minecraft:audio/firefly_bush_sounds")]
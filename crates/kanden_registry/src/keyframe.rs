@@ -0,0 +1,138 @@
+//! Piecewise-linear keyframe interpolation for [`EnvironmentAttribute`] values
+//! that vary over world time, such as fog distances, sky/cloud colors, and
+//! sun/moon/star angles.
+//!
+//! [`EnvironmentAttribute`]: crate::environment_attribute::EnvironmentAttribute
+
+use serde::{Deserialize, Serialize};
+
+use crate::serde::{ARGB, RGB};
+
+/// A single `{ ticks, value }` point in a [`Keyframed`] animation.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe<T> {
+    pub ticks: u32,
+    pub value: T,
+}
+
+/// A value that is either a constant, or driven by piecewise-linear
+/// interpolation between a list of [`Keyframe`]s as world time advances.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Keyframed<T> {
+    Constant(T),
+    Animated(Vec<Keyframe<T>>),
+}
+
+impl<T: Lerp + Copy> Keyframed<T> {
+    /// Returns the value at `tick`.
+    ///
+    /// For an animated value, this is the piecewise-linear interpolation
+    /// between the two keyframes bracketing `tick`; before the first
+    /// keyframe the first value is held, and after the last keyframe the
+    /// last value is held. Returns `None` only if the keyframe list is
+    /// empty.
+    pub fn value_at(&self, tick: u32) -> Option<T> {
+        match self {
+            Keyframed::Constant(value) => Some(*value),
+            Keyframed::Animated(keyframes) => {
+                let first = keyframes.first()?;
+                if tick <= first.ticks {
+                    return Some(first.value);
+                }
+
+                let last = keyframes.last()?;
+                if tick >= last.ticks {
+                    return Some(last.value);
+                }
+
+                let (a, b) = keyframes
+                    .windows(2)
+                    .map(|w| (w[0], w[1]))
+                    .find(|(_, b)| tick <= b.ticks)?;
+
+                let span = (b.ticks - a.ticks).max(1) as f32;
+                let t = (tick - a.ticks) as f32 / span;
+
+                Some(a.value.lerp(b.value, t))
+            }
+        }
+    }
+}
+
+impl<T> From<T> for Keyframed<T> {
+    fn from(value: T) -> Self {
+        Self::Constant(value)
+    }
+}
+
+/// Linear interpolation between two values of the same type.
+pub trait Lerp {
+    /// Returns the point `t` of the way from `self` to `other`, where `t` is
+    /// expected to be in `0.0..=1.0`.
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+/// A full-turn celestial angle in `[0.0, 1.0)`, following vanilla's "sky
+/// angle" convention (`0.0` at sunrise, wrapping back to `0.0` after a full
+/// rotation) -- used by [`EnvironmentAttribute::SunAngle`],
+/// [`MoonAngle`](EnvironmentAttribute::MoonAngle), and
+/// [`StarAngle`](EnvironmentAttribute::StarAngle).
+///
+/// Interpolating the raw `f32` with [`Lerp for f32`](Lerp) would cut straight
+/// through the numeric gap at the `0.0`/`1.0` seam instead of wrapping around
+/// it (e.g. animating from `0.9` to `0.1` would swing back through `0.5`
+/// rather than forward through `1.0`); [`Angle`]'s `lerp` takes the shorter
+/// way around the wrap instead.
+///
+/// [`EnvironmentAttribute::SunAngle`]: crate::environment_attribute::EnvironmentAttribute::SunAngle
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(transparent)]
+pub struct Angle(pub f32);
+
+impl Lerp for Angle {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        let a = self.0.rem_euclid(1.0);
+        let b = other.0.rem_euclid(1.0);
+
+        let mut delta = b - a;
+        if delta > 0.5 {
+            delta -= 1.0;
+        } else if delta < -0.5 {
+            delta += 1.0;
+        }
+
+        Angle((a + delta * t).rem_euclid(1.0))
+    }
+}
+
+impl Lerp for RGB {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        RGB::from_rgb(
+            lerp_u8(self.red(), other.red(), t),
+            lerp_u8(self.green(), other.green(), t),
+            lerp_u8(self.blue(), other.blue(), t),
+        )
+    }
+}
+
+impl Lerp for ARGB {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        ARGB::from_argb(
+            lerp_u8(self.alpha(), other.alpha(), t),
+            lerp_u8(self.red(), other.red(), t),
+            lerp_u8(self.green(), other.green(), t),
+            lerp_u8(self.blue(), other.blue(), t),
+        )
+    }
+}
+
+fn lerp_u8(a: u8, b: u8, t: f32) -> u8 {
+    (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8
+}
@@ -0,0 +1,226 @@
+//! Lua plugin scripting, following the embedded-plugin approach other small
+//! Minecraft servers take: operators drop `.lua` files into a plugins
+//! directory, and each script subscribes to the events
+//! [`kanden_server::client_command`] already emits (`SprintEvent`,
+//! `SneakEvent`, `JumpWithHorseEvent`, `LeaveBedEvent`) by calling the
+//! global `on(event, handler)` function.
+//!
+//! A handler is called as `handler(client, state)` (`client` is the
+//! client's [`Entity`] bits as an integer, `state` a lowercase string like
+//! `"start"`/`"stop"`), and returning the string `"cancel"` vetoes the
+//! mutation `kanden_server::client_command` would otherwise apply for that
+//! command this tick -- see [`ClientCommandSet::Hooks`].
+//!
+//! ```lua
+//! on("sprint", function(client, state)
+//!     if state == "start" then
+//!         return "cancel"
+//!     end
+//! end)
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use kanden_server::client_command::{
+    CancelledClientCommands, ClientCommandKind, ClientCommandSet, JumpWithHorseEvent,
+    JumpWithHorseState, LeaveBedEvent, SneakEvent, SneakState, SprintEvent, SprintState,
+};
+use kanden_server::EventLoopPreUpdate;
+use mlua::{Lua, Table, Value};
+
+/// Loads every `*.lua` file directly under `plugins_dir` into a shared
+/// [`Lua`] instance on startup, and wires the four `kanden_server`
+/// client-command events into it.
+pub struct ScriptingPlugin {
+    pub plugins_dir: PathBuf,
+}
+
+impl Default for ScriptingPlugin {
+    fn default() -> Self {
+        Self {
+            plugins_dir: PathBuf::from("plugins"),
+        }
+    }
+}
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        let lua = load_scripts(&self.plugins_dir);
+
+        // `Lua` isn't `Send`/`Sync` (it's a handle to a single-threaded
+        // interpreter), so it lives as a non-send resource rather than a
+        // regular `Resource`.
+        app.insert_non_send_resource(lua).add_systems(
+            EventLoopPreUpdate,
+            (
+                dispatch_sprint,
+                dispatch_sneak,
+                dispatch_jump_with_horse,
+                dispatch_leave_bed,
+            )
+                .in_set(ClientCommandSet::Hooks),
+        );
+    }
+}
+
+fn load_scripts(plugins_dir: &Path) -> Lua {
+    let lua = Lua::new();
+    install_on_function(&lua);
+
+    let Ok(entries) = std::fs::read_dir(plugins_dir) else {
+        tracing::info!(
+            "no plugins directory at '{}', scripting disabled",
+            plugins_dir.display()
+        );
+        return lua;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("lua") {
+            continue;
+        }
+
+        let result = std::fs::read_to_string(&path)
+            .map_err(mlua::Error::external)
+            .and_then(|source| lua.load(&source).set_name(&path.to_string_lossy()).exec());
+
+        match result {
+            Ok(()) => tracing::info!("loaded script '{}'", path.display()),
+            Err(e) => tracing::error!("failed to load script '{}': {e:#}", path.display()),
+        }
+    }
+
+    lua
+}
+
+/// Registers the global `on(event, handler)` Lua function scripts call to
+/// subscribe; handlers for the same `event` accumulate in registration
+/// order rather than overwriting each other.
+fn install_on_function(lua: &Lua) {
+    lua.globals()
+        .set(
+            "_kanden_handlers",
+            lua.create_table().expect("create handler registry table"),
+        )
+        .expect("set handler registry table");
+
+    let on = lua
+        .create_function(|lua, (event, handler): (String, mlua::Function)| {
+            let registry: Table = lua.globals().get("_kanden_handlers")?;
+            let for_event = match registry.get::<_, Value>(event.clone())? {
+                Value::Table(table) => table,
+                _ => {
+                    let table = lua.create_table()?;
+                    registry.set(event, table.clone())?;
+                    table
+                }
+            };
+
+            for_event.set(for_event.raw_len() + 1, handler)?;
+            Ok(())
+        })
+        .expect("create 'on' function");
+
+    lua.globals().set("on", on).expect("set 'on' function");
+}
+
+/// Calls every handler registered for `event` with `(client_bits, state)`
+/// and reports whether any of them returned `"cancel"`.
+///
+/// A handler erroring is logged and treated as "did not cancel" rather
+/// than aborting the remaining handlers for this event.
+fn invoke(lua: &Lua, event: &str, client: Entity, state: &str) -> bool {
+    let handlers: mlua::Result<Table> = (|| {
+        let registry: Table = lua.globals().get("_kanden_handlers")?;
+        registry.get(event)
+    })();
+
+    let Ok(handlers) = handlers else {
+        return false;
+    };
+
+    let mut cancelled = false;
+    for handler in handlers.sequence_values::<mlua::Function>() {
+        let handler = match handler {
+            Ok(handler) => handler,
+            Err(e) => {
+                tracing::error!("malformed handler registered for '{event}': {e:#}");
+                continue;
+            }
+        };
+
+        match handler.call::<_, Option<String>>((client.to_bits() as i64, state)) {
+            Ok(Some(result)) if result == "cancel" => cancelled = true,
+            Ok(_) => {}
+            Err(e) => tracing::error!("lua handler for '{event}' errored: {e:#}"),
+        }
+    }
+
+    cancelled
+}
+
+fn dispatch_sprint(
+    lua: NonSend<Lua>,
+    mut events: EventReader<SprintEvent>,
+    mut cancelled: ResMut<CancelledClientCommands>,
+) {
+    for event in events.read() {
+        let state = match event.state {
+            SprintState::Start => "start",
+            SprintState::Stop => "stop",
+        };
+
+        if invoke(&lua, "sprint", event.client, state) {
+            cancelled.cancel(event.client, ClientCommandKind::Sprint);
+        }
+    }
+}
+
+fn dispatch_sneak(
+    lua: NonSend<Lua>,
+    mut events: EventReader<SneakEvent>,
+    mut cancelled: ResMut<CancelledClientCommands>,
+) {
+    for event in events.read() {
+        let state = match event.state {
+            SneakState::Start => "start",
+            SneakState::Stop => "stop",
+        };
+
+        if invoke(&lua, "sneak", event.client, state) {
+            cancelled.cancel(event.client, ClientCommandKind::Sneak);
+        }
+    }
+}
+
+fn dispatch_jump_with_horse(
+    lua: NonSend<Lua>,
+    mut events: EventReader<JumpWithHorseEvent>,
+    mut cancelled: ResMut<CancelledClientCommands>,
+) {
+    for event in events.read() {
+        let state = match event.state {
+            JumpWithHorseState::Start { .. } => "start",
+            JumpWithHorseState::Stop => "stop",
+        };
+
+        if invoke(&lua, "jump_with_horse", event.client, state) {
+            cancelled.cancel(event.client, ClientCommandKind::JumpWithHorse);
+        }
+    }
+}
+
+fn dispatch_leave_bed(
+    lua: NonSend<Lua>,
+    mut events: EventReader<LeaveBedEvent>,
+    mut cancelled: ResMut<CancelledClientCommands>,
+) {
+    for event in events.read() {
+        if invoke(&lua, "leave_bed", event.client, "leave") {
+            cancelled.cancel(event.client, ClientCommandKind::LeaveBed);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
 use kanden_entity::{
@@ -20,7 +22,111 @@ impl Plugin for ClientCommandPlugin {
             .add_event::<SneakEvent>()
             .add_event::<JumpWithHorseEvent>()
             .add_event::<LeaveBedEvent>()
-            .add_systems(EventLoopPreUpdate, handle_client_command);
+            .init_resource::<PendingClientCommands>()
+            .init_resource::<CancelledClientCommands>()
+            .configure_sets(
+                EventLoopPreUpdate,
+                (
+                    ClientCommandSet::Parse,
+                    ClientCommandSet::Hooks,
+                    ClientCommandSet::Apply,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                EventLoopPreUpdate,
+                (
+                    parse_client_command.in_set(ClientCommandSet::Parse),
+                    apply_client_command.in_set(ClientCommandSet::Apply),
+                ),
+            );
+    }
+}
+
+/// Ordering anchor for plugins (e.g. `kanden_scripting`) that need to react
+/// to a client command before it's applied to entity state, and possibly
+/// veto it via [`CancelledClientCommands`].
+///
+/// `Parse` turns `PlayerInputC2s`/`PlayerCommandC2s` packets into
+/// [`PendingClientCommands`] and the public `*Event`s below; apart from
+/// copying the raw input flags into `PlayerInputState`, it mutates no entity
+/// state. `Hooks` is where scripts observe those
+/// events and decide whether to cancel the pending mutation. `Apply`
+/// performs whatever wasn't cancelled.
+#[derive(SystemSet, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ClientCommandSet {
+    Parse,
+    Hooks,
+    Apply,
+}
+
+/// Identifies a pending mutation for [`CancelledClientCommands`], stripped
+/// of its payload (a client can only have one command of a given kind
+/// pending per tick).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ClientCommandKind {
+    Sprint,
+    Sneak,
+    JumpWithHorse,
+    LeaveBed,
+}
+
+/// A client command parsed out of this tick's packets but not yet applied
+/// to entity state -- see [`ClientCommandSet`].
+#[derive(Clone, Debug)]
+enum PendingClientCommand {
+    Sprint { client: Entity, state: SprintState },
+    Sneak { client: Entity, state: SneakState },
+    JumpWithHorse {
+        client: Entity,
+        state: JumpWithHorseState,
+    },
+    LeaveBed { client: Entity },
+    /// Not part of `ClientCommandKind`/cancellable today -- there's no
+    /// public event for it yet, matching the pre-existing `OpenInventory`
+    /// TODO below it. Always applied.
+    FallFlying { client: Entity },
+}
+
+impl PendingClientCommand {
+    fn client(&self) -> Entity {
+        match *self {
+            Self::Sprint { client, .. }
+            | Self::Sneak { client, .. }
+            | Self::JumpWithHorse { client, .. }
+            | Self::LeaveBed { client }
+            | Self::FallFlying { client } => client,
+        }
+    }
+
+    fn kind(&self) -> Option<ClientCommandKind> {
+        match self {
+            Self::Sprint { .. } => Some(ClientCommandKind::Sprint),
+            Self::Sneak { .. } => Some(ClientCommandKind::Sneak),
+            Self::JumpWithHorse { .. } => Some(ClientCommandKind::JumpWithHorse),
+            Self::LeaveBed { .. } => Some(ClientCommandKind::LeaveBed),
+            Self::FallFlying { .. } => None,
+        }
+    }
+}
+
+/// Commands parsed this tick, queued between [`ClientCommandSet::Parse`]
+/// and [`ClientCommandSet::Apply`].
+#[derive(Resource, Default)]
+struct PendingClientCommands(Vec<PendingClientCommand>);
+
+/// Commands a [`ClientCommandSet::Hooks`] system has vetoed -- e.g. a Lua
+/// script returning a cancel result for a `SprintEvent`.
+///
+/// Entries are consumed (and thus cleared) by [`apply_client_command`]
+/// every tick, so a hook only needs to call [`cancel`](Self::cancel) for
+/// commands it observed this tick.
+#[derive(Resource, Default)]
+pub struct CancelledClientCommands(HashSet<(Entity, ClientCommandKind)>);
+
+impl CancelledClientCommands {
+    pub fn cancel(&mut self, client: Entity, kind: ClientCommandKind) {
+        self.0.insert((client, kind));
     }
 }
 
@@ -68,39 +174,48 @@ pub struct LeaveBedEvent {
     pub client: Entity,
 }
 
-fn handle_client_command(
+/// Parses `PlayerInputC2s`/`PlayerCommandC2s` into [`PendingClientCommand`]s
+/// and sends the corresponding public events. It copies the raw input flags
+/// into `PlayerInputState` (not cancellable), but applies no pose or
+/// shared-flag mutation: that's deferred to [`apply_client_command`] so a
+/// [`ClientCommandSet::Hooks`] system (e.g. `kanden_scripting`) gets a
+/// chance to veto it first via [`CancelledClientCommands`].
+fn parse_client_command(
     mut packets: EventReader<PacketEvent>,
-    mut clients: Query<(
-        &mut entity::DataPose,
-        &mut DataSharedFlags,
-        &mut PlayerInputState,
-    )>,
+    mut clients: Query<(&DataSharedFlags, &mut PlayerInputState)>,
+    mut pending: ResMut<PendingClientCommands>,
     mut sprinting_events: EventWriter<SprintEvent>,
     mut sneaking_events: EventWriter<SneakEvent>,
     mut jump_with_horse_events: EventWriter<JumpWithHorseEvent>,
     mut leave_bed_events: EventWriter<LeaveBedEvent>,
 ) {
+    pending.0.clear();
+
     for packet in packets.read() {
         if let Some(pkt) = packet.decode::<PlayerInputC2s>() {
-            if let Ok((mut pose, mut flags, mut input_state)) = clients.get_mut(packet.client) {
+            if let Ok((flags, mut input_state)) = clients.get_mut(packet.client) {
                 if !flags.sneaking() && pkt.flags.sneak() {
+                    let state = SneakState::Start;
                     sneaking_events.send(SneakEvent {
                         client: packet.client,
-                        state: SneakState::Start,
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::Sneak {
+                        client: packet.client,
+                        state,
                     });
-
-                    pose.0 = Pose::Sneaking;
-                    flags.set_sneaking(true);
                 }
 
                 if flags.sneaking() && !pkt.flags.sneak() {
+                    let state = SneakState::Stop;
                     sneaking_events.send(SneakEvent {
                         client: packet.client,
-                        state: SneakState::Stop,
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::Sneak {
+                        client: packet.client,
+                        state,
                     });
-
-                    pose.0 = Pose::Standing;
-                    flags.set_sneaking(false);
                 }
 
                 input_state.forward = pkt.flags.forward();
@@ -118,46 +233,61 @@ fn handle_client_command(
                     leave_bed_events.send(LeaveBedEvent {
                         client: packet.client,
                     });
+                    pending.0.push(PendingClientCommand::LeaveBed {
+                        client: packet.client,
+                    });
                 }
                 PlayerCommand::StartSprinting => {
-                    if let Ok((_, mut flags, _)) = clients.get_mut(packet.client) {
-                        flags.set_sprinting(true);
-                    }
-
+                    let state = SprintState::Start;
                     sprinting_events.send(SprintEvent {
                         client: packet.client,
-                        state: SprintState::Start,
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::Sprint {
+                        client: packet.client,
+                        state,
                     });
                 }
                 PlayerCommand::StopSprinting => {
-                    if let Ok((_, mut flags, _)) = clients.get_mut(packet.client) {
-                        flags.set_sprinting(false);
-                    }
-
+                    let state = SprintState::Stop;
                     sprinting_events.send(SprintEvent {
                         client: packet.client,
-                        state: SprintState::Stop,
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::Sprint {
+                        client: packet.client,
+                        state,
                     });
                 }
                 PlayerCommand::StartRidingJump => {
+                    let state = JumpWithHorseState::Start {
+                        power: pkt.jump_boost.0 as u8,
+                    };
                     jump_with_horse_events.send(JumpWithHorseEvent {
                         client: packet.client,
-                        state: JumpWithHorseState::Start {
-                            power: pkt.jump_boost.0 as u8,
-                        },
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::JumpWithHorse {
+                        client: packet.client,
+                        state,
                     });
                 }
                 PlayerCommand::StopRidingJump => {
+                    let state = JumpWithHorseState::Stop;
                     jump_with_horse_events.send(JumpWithHorseEvent {
                         client: packet.client,
-                        state: JumpWithHorseState::Stop,
+                        state,
+                    });
+                    pending.0.push(PendingClientCommand::JumpWithHorse {
+                        client: packet.client,
+                        state,
                     });
                 }
                 PlayerCommand::OpenInventory => {} // TODO
                 PlayerCommand::StartFallFlying => {
-                    if let Ok((mut pose, _, _)) = clients.get_mut(packet.client) {
-                        pose.0 = Pose::FallFlying;
-                    }
+                    pending.0.push(PendingClientCommand::FallFlying {
+                        client: packet.client,
+                    });
 
                     // TODO.
                 }
@@ -165,3 +295,54 @@ fn handle_client_command(
         }
     }
 }
+
+/// Applies every [`PendingClientCommand`] queued by [`parse_client_command`]
+/// that a [`ClientCommandSet::Hooks`] system hasn't cancelled.
+fn apply_client_command(
+    mut pending: ResMut<PendingClientCommands>,
+    mut cancelled: ResMut<CancelledClientCommands>,
+    mut clients: Query<(&mut entity::DataPose, &mut DataSharedFlags)>,
+) {
+    for command in pending.0.drain(..) {
+        let client = command.client();
+
+        if let Some(kind) = command.kind() {
+            if cancelled.0.remove(&(client, kind)) {
+                continue;
+            }
+        }
+
+        let Ok((mut pose, mut flags)) = clients.get_mut(client) else {
+            continue;
+        };
+
+        match command {
+            PendingClientCommand::Sneak { state, .. } => match state {
+                SneakState::Start => {
+                    pose.0 = Pose::Sneaking;
+                    flags.set_sneaking(true);
+                }
+                SneakState::Stop => {
+                    pose.0 = Pose::Standing;
+                    flags.set_sneaking(false);
+                }
+            },
+            PendingClientCommand::Sprint { state, .. } => match state {
+                SprintState::Start => flags.set_sprinting(true),
+                SprintState::Stop => flags.set_sprinting(false),
+            },
+            PendingClientCommand::JumpWithHorse { .. } => {
+                // No entity state tied to this yet -- see the
+                // `OpenInventory` TODO in `parse_client_command`.
+            }
+            PendingClientCommand::LeaveBed { .. } => {}
+            PendingClientCommand::FallFlying { .. } => {
+                pose.0 = Pose::FallFlying;
+            }
+        }
+    }
+
+    // Any cancellation a hook registered for a command that never made it
+    // into `pending` (e.g. a stale client) would otherwise linger forever.
+    cancelled.0.clear();
+}
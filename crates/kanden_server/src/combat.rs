@@ -0,0 +1,144 @@
+//! A data-driven combat API: damage application, knockback, and a
+//! configurable post-hit invulnerability window, all driven by
+//! `DamageType` entries from the `kanden_registry::damage_type` registry.
+//!
+//! This generalizes what `examples/combat.rs` used to do by hand — a
+//! hardcoded `source_type_id`, a fixed 10-tick attack cooldown, and manually
+//! written `DamageEventS2c`/`HurtAnimationS2c` packets — into a reusable
+//! [`ApplyDamage`] event that any game mode can fire.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use kanden_ident::Ident;
+use kanden_protocol::lpvec::LpVec3;
+use kanden_protocol::math::{DVec3, Vec3Swizzles};
+use kanden_protocol::packets::play::{DamageEventS2c, HurtAnimationS2c};
+use kanden_protocol::{VarInt, WritePacket};
+use kanden_registry::damage_type::DamageTypeRegistry;
+use tracing::warn;
+
+use crate::client::VisibleChunkLayer;
+use crate::entity::living::DataHealth;
+use crate::entity::{EntityId, Look, OnGround, Position, Velocity};
+use crate::layer::ChunkLayer;
+use crate::Server;
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyDamage>()
+            .add_systems(PostUpdate, apply_damage_events);
+    }
+}
+
+/// Damages `target` for `amount` using the `damage_type` entry from
+/// [`DamageTypeRegistry`]. `attacker` and `source_pos` are both optional
+/// since not every source of damage (fall damage, fire, the void) has an
+/// attacking entity or even a fixed point in space.
+#[derive(Event, Clone, Debug)]
+pub struct ApplyDamage {
+    pub target: Entity,
+    pub attacker: Option<Entity>,
+    pub amount: f32,
+    pub damage_type: Ident<String>,
+    pub source_pos: Option<DVec3>,
+}
+
+/// Tracks how recently an entity was last hurt, so it can be made briefly
+/// immune to further hits. Generalizes the `last_attacked_tick`/10-tick
+/// cooldown that used to live in `examples/combat.rs`'s `CombatState`.
+///
+/// `window_ticks` is set from the triggering damage type's
+/// `invulnerability_ticks` every time a hit lands. Hits that arrive while
+/// the entity is still invulnerable are ignored and leave the window as is.
+#[derive(Component, Default, Debug)]
+pub struct Invulnerability {
+    last_hit_tick: i64,
+    window_ticks: u32,
+}
+
+impl Invulnerability {
+    pub fn is_invulnerable(&self, current_tick: i64) -> bool {
+        current_tick - self.last_hit_tick < i64::from(self.window_ticks)
+    }
+}
+
+fn apply_damage_events(
+    server: Res<Server>,
+    damage_types: Res<DamageTypeRegistry>,
+    mut events: EventReader<ApplyDamage>,
+    mut targets: Query<(
+        &EntityId,
+        &mut DataHealth,
+        &mut Velocity,
+        &OnGround,
+        &Position,
+        &Look,
+        &VisibleChunkLayer,
+        &mut Invulnerability,
+    )>,
+    attackers: Query<(&EntityId, &Position)>,
+    mut layers: Query<&mut ChunkLayer>,
+) {
+    for event in events.read() {
+        let Ok((entity_id, mut health, mut velocity, on_ground, pos, look, visible_layer, mut inv)) =
+            targets.get_mut(event.target)
+        else {
+            continue;
+        };
+
+        let current_tick = server.current_tick();
+
+        if inv.is_invulnerable(current_tick) {
+            continue;
+        }
+
+        let Some(damage_type_id) = damage_types.id_of(&event.damage_type) else {
+            warn!("ApplyDamage referenced unknown damage type {}", event.damage_type);
+            continue;
+        };
+        let Some(damage_type) = damage_types.get(damage_type_id) else {
+            continue;
+        };
+
+        inv.last_hit_tick = current_tick;
+        inv.window_ticks = damage_type.invulnerability_ticks;
+
+        health.0 = (health.0 - event.amount).max(0.0);
+
+        let attacker_entity_id = event.attacker.and_then(|a| attackers.get(a).ok());
+        let source_pos = event
+            .source_pos
+            .or_else(|| attacker_entity_id.map(|(_, attacker_pos)| attacker_pos.0));
+
+        if let Some(source_pos) = source_pos {
+            let dir = (pos.0.xz() - source_pos.xz())
+                .normalize_or_zero()
+                .as_vec2();
+            velocity.apply_knockback(
+                0.5 * damage_type.knockback_multiplier,
+                dir.x,
+                dir.y,
+                on_ground.0,
+            );
+        }
+
+        let Ok(mut layer) = layers.get_mut(visible_layer.0) else {
+            continue;
+        };
+
+        let mut layer_writer = layer.view_writer(pos.0);
+        layer_writer.write_packet(&DamageEventS2c {
+            entity_id: VarInt(entity_id.get()),
+            source_type_id: VarInt(i32::from(damage_type_id.get_value())),
+            source_cause_id: attacker_entity_id.map_or(VarInt(-1), |(id, _)| VarInt(id.get())),
+            source_direct_id: VarInt(-1),
+            source_pos: source_pos.map(LpVec3::from),
+        });
+        layer_writer.write_packet(&HurtAnimationS2c {
+            entity_id: VarInt(entity_id.get()),
+            yaw: look.yaw,
+        });
+    }
+}
@@ -0,0 +1,267 @@
+//! Cookie storage and cross-server client transfer.
+//!
+//! Cookies are opaque, server-chosen key/value pairs the client retains and
+//! re-sends on request — including after a [`Transfer`] to a different
+//! server — which makes them the protocol's mechanism for carrying
+//! continuation state (a session token, a lobby's matchmaking ticket, ...)
+//! through a hand-off between servers that otherwise share no state.
+//!
+//! [`Cookies`] mirrors the last value this server observed for each key, so
+//! plugins can read a cookie without a network round-trip once the client
+//! has answered a request for it at least once. [`CookieStore`] is the
+//! other direction: cookies this server wants the client to remember but
+//! hasn't sent yet, flushed as a batch by [`TransferClient`] so a
+//! hand-off's continuation state always reaches the client before the
+//! packet that sends it away does.
+
+use std::collections::HashMap;
+
+use bevy_app::prelude::*;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use kanden_ident::Ident;
+use kanden_protocol::packets::play::{
+    CookieRequestS2c, CookieResponseC2s, StoreCookieS2c, TransferS2c,
+};
+use kanden_protocol::{VarInt, WritePacket};
+
+use crate::client::Client;
+use crate::event_loop::{EventLoopPreUpdate, PacketEvent};
+
+pub struct CookiePlugin;
+
+impl Plugin for CookiePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CookieReceived>()
+            .add_event::<CookieAwaitResolved>()
+            .add_event::<Transfer>()
+            .add_event::<TransferInitiated>()
+            .add_event::<TransferClient>()
+            .add_systems(
+                EventLoopPreUpdate,
+                (
+                    handle_cookie_response,
+                    resolve_awaited_cookies,
+                    apply_transfers,
+                    apply_client_transfers,
+                ),
+            );
+    }
+}
+
+/// The cookies this server has observed from a client, keyed by the
+/// cookie's [`Ident`].
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct Cookies(pub HashMap<Ident<String>, Vec<u8>>);
+
+/// Cookies queued to be sent to a client via [`StoreCookieS2c`] but not
+/// written to the connection yet.
+///
+/// Gameplay code that wants a value to survive a transfer calls
+/// [`queue`](Self::queue) rather than [`store_cookie`] directly, so every
+/// queued cookie flushes together right before the client leaves — see
+/// [`TransferClient`].
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct CookieStore(pub HashMap<Ident<String>, Vec<u8>>);
+
+impl CookieStore {
+    /// Queues `payload` to be sent under `key` the next time this client's
+    /// store is flushed.
+    pub fn queue(&mut self, key: Ident<String>, payload: Vec<u8>) {
+        self.0.insert(key, payload);
+    }
+
+    /// Removes and returns every queued cookie, in no particular order.
+    fn drain(&mut self) -> Vec<(Ident<String>, Vec<u8>)> {
+        self.0.drain().collect()
+    }
+}
+
+/// Sends a [`CookieRequestS2c`] for `key` and returns without waiting; the
+/// matching [`CookieResponseC2s`] is handled by [`handle_cookie_response`]
+/// when it arrives (see [`await_cookie`] to be notified of it).
+pub fn request_cookie(client: &mut Client, key: &Ident<String>) {
+    client.write_packet(&CookieRequestS2c {
+        key: key.clone().into(),
+    });
+}
+
+/// Sends a [`StoreCookieS2c`] asking the client to remember `payload` under
+/// `key` immediately, bypassing [`CookieStore`].
+pub fn store_cookie(client: &mut Client, key: &Ident<String>, payload: &[u8]) {
+    client.write_packet(&StoreCookieS2c {
+        key: key.clone().into(),
+        payload: payload.into(),
+    });
+}
+
+/// Sent once a client's [`CookieResponseC2s`] has been received and
+/// mirrored into its [`Cookies`] component.
+///
+/// `payload` is `None` if the client didn't have a cookie stored under
+/// `key`.
+#[derive(Event, Clone, Debug)]
+pub struct CookieReceived {
+    pub client: Entity,
+    pub key: Ident<String>,
+    pub payload: Option<Vec<u8>>,
+}
+
+fn handle_cookie_response(
+    mut packets: EventReader<PacketEvent>,
+    mut clients: Query<&mut Cookies>,
+    mut cookie_received: EventWriter<CookieReceived>,
+) {
+    for packet in packets.read() {
+        if let Some(pkt) = packet.decode::<CookieResponseC2s>() {
+            let key: Ident<String> = pkt.key.clone().into();
+            let payload = pkt.payload.as_ref().map(|bytes| bytes.to_vec());
+
+            if let Ok(mut cookies) = clients.get_mut(packet.client) {
+                match &payload {
+                    Some(payload) => {
+                        cookies.insert(key.clone(), payload.clone());
+                    }
+                    None => {
+                        cookies.remove(&key);
+                    }
+                }
+            }
+
+            cookie_received.send(CookieReceived {
+                client: packet.client,
+                key,
+                payload,
+            });
+        }
+    }
+}
+
+/// Marks a client as paused — typically mid-`PacketState::Configuration`,
+/// e.g. right after a [`Transfer`] — until the [`CookieResponseC2s`] for
+/// `key` arrives.
+///
+/// Systems that gate a client's progress out of Configuration (not part of
+/// this checkout) should treat this component's presence as "not ready
+/// yet" and [`CookieAwaitResolved`] as the signal to re-check.
+#[derive(Component, Clone, Debug)]
+pub struct AwaitingCookie {
+    pub key: Ident<String>,
+}
+
+/// Requests `key` from `client` and marks it [`AwaitingCookie`], so the
+/// caller can wait for [`CookieAwaitResolved`] instead of polling
+/// [`Cookies`] every tick.
+pub fn await_cookie(commands: &mut Commands, client: &mut Client, entity: Entity, key: Ident<String>) {
+    request_cookie(client, &key);
+    commands.entity(entity).insert(AwaitingCookie { key });
+}
+
+/// Sent once the [`CookieResponseC2s`] a client was [`AwaitingCookie`] for
+/// has arrived; `payload` mirrors [`CookieReceived::payload`].
+#[derive(Event, Clone, Debug)]
+pub struct CookieAwaitResolved {
+    pub client: Entity,
+    pub payload: Option<Vec<u8>>,
+}
+
+fn resolve_awaited_cookies(
+    mut commands: Commands,
+    mut cookie_received: EventReader<CookieReceived>,
+    awaiting: Query<&AwaitingCookie>,
+    mut resolved: EventWriter<CookieAwaitResolved>,
+) {
+    for event in cookie_received.read() {
+        let Ok(awaiting) = awaiting.get(event.client) else {
+            continue;
+        };
+
+        if awaiting.key != event.key {
+            continue;
+        }
+
+        commands.entity(event.client).remove::<AwaitingCookie>();
+        resolved.send(CookieAwaitResolved {
+            client: event.client,
+            payload: event.payload.clone(),
+        });
+    }
+}
+
+/// Sends `client` to another server at `host:port`.
+///
+/// The client's already-stored cookies travel with it automatically — the
+/// destination server can request the same keys back with
+/// [`request_cookie`] to resume whatever session/auth state they encode.
+/// Prefer [`TransferClient`] when `client` has anything queued in
+/// [`CookieStore`], since this event doesn't flush it first.
+#[derive(Event, Clone, Debug)]
+pub struct Transfer {
+    pub client: Entity,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Sent after [`Transfer`] (or [`TransferClient`]) has been applied, i.e.
+/// after the [`TransferS2c`] packet was written to the client.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct TransferInitiated {
+    pub client: Entity,
+}
+
+fn apply_transfers(
+    mut transfers: EventReader<Transfer>,
+    mut clients: Query<&mut Client>,
+    mut transfer_initiated: EventWriter<TransferInitiated>,
+) {
+    for Transfer { client, host, port } in transfers.read() {
+        let Ok(mut client_ref) = clients.get_mut(*client) else {
+            continue;
+        };
+
+        write_transfer(&mut client_ref, host, *port);
+        transfer_initiated.send(TransferInitiated { client: *client });
+    }
+}
+
+fn write_transfer(client: &mut Client, host: &str, port: u16) {
+    client.write_packet(&TransferS2c {
+        host: host.into(),
+        port: VarInt(i32::from(port)),
+    });
+}
+
+/// Sends `client` to another server, flushing its [`CookieStore`] (if any)
+/// first so continuation state queued with [`CookieStore::queue`] is
+/// guaranteed to reach the client before [`TransferS2c`] does.
+///
+/// The preferred, high-level entry point for a transfer: reach for
+/// [`Transfer`] directly only when `client` has nothing queued in
+/// [`CookieStore`] and the one-packet send is enough.
+#[derive(Event, Clone, Debug)]
+pub struct TransferClient {
+    pub client: Entity,
+    pub host: String,
+    pub port: u16,
+}
+
+fn apply_client_transfers(
+    mut transfers: EventReader<TransferClient>,
+    mut clients: Query<(&mut Client, Option<&mut CookieStore>)>,
+    mut transfer_initiated: EventWriter<TransferInitiated>,
+) {
+    for TransferClient { client, host, port } in transfers.read() {
+        let Ok((mut client_ref, store)) = clients.get_mut(*client) else {
+            continue;
+        };
+
+        if let Some(mut store) = store {
+            for (key, payload) in store.drain() {
+                store_cookie(&mut client_ref, &key, &payload);
+            }
+        }
+
+        write_transfer(&mut client_ref, host, *port);
+        transfer_initiated.send(TransferInitiated { client: *client });
+    }
+}
@@ -0,0 +1,80 @@
+//! Server-driven dialogs: native forms (notices, confirmations, multi-action
+//! menus, and input fields) a plugin can show a client without a resource
+//! pack.
+//!
+//! [`ShowDialog`]/[`ClearDialog`] are blanket-implemented client-facing
+//! conveniences, mirroring [`SetTitle`](crate::title::SetTitle). Submissions
+//! come back as [`DialogSubmitEvent`], decoded from the client's
+//! `CustomClickActionC2s`.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use kanden_ident::Ident;
+use kanden_nbt::Compound;
+use kanden_protocol::packets::common::{ClearDialogS2c, CustomClickActionC2s, Dialog, ShowDialogS2c};
+use kanden_protocol::WritePacket;
+
+use crate::event_loop::{EventLoopPreUpdate, PacketEvent};
+
+pub struct DialogPlugin;
+
+impl Plugin for DialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DialogSubmitEvent>()
+            .add_systems(EventLoopPreUpdate, handle_dialog_submissions);
+    }
+}
+
+/// Shows a [`Dialog`] to anything that can receive packets, such as a
+/// [`Client`](crate::client::Client).
+pub trait ShowDialog: WritePacket {
+    fn show_dialog(&mut self, dialog: Dialog) {
+        self.write_packet(&ShowDialogS2c { dialog });
+    }
+}
+
+impl<T: WritePacket> ShowDialog for T {}
+
+/// Clears a shown [`Dialog`] from anything that can receive packets.
+pub trait ClearDialog: WritePacket {
+    /// Clears the dialog with the given id, if still open.
+    fn clear_dialog(&mut self, target: Ident<String>) {
+        self.write_packet(&ClearDialogS2c {
+            target: Some(target.into()),
+        });
+    }
+
+    /// Clears whatever dialog is currently open.
+    fn clear_any_dialog(&mut self) {
+        self.write_packet(&ClearDialogS2c { target: None });
+    }
+}
+
+impl<T: WritePacket> ClearDialog for T {}
+
+/// Sent when a client clicks a `DialogAction` inside a shown dialog.
+///
+/// `payload` holds the input fields' submitted values (see
+/// `kanden_protocol::packets::common::parse_submission`) for an
+/// `InputDialog`, and is `None` for dialogs with no fields to submit.
+#[derive(Event, Clone, Debug)]
+pub struct DialogSubmitEvent {
+    pub client: Entity,
+    pub action: Ident<String>,
+    pub payload: Option<Compound>,
+}
+
+fn handle_dialog_submissions(
+    mut packets: EventReader<PacketEvent>,
+    mut dialog_submit: EventWriter<DialogSubmitEvent>,
+) {
+    for packet in packets.read() {
+        if let Some(pkt) = packet.decode::<CustomClickActionC2s>() {
+            dialog_submit.send(DialogSubmitEvent {
+                client: packet.client,
+                action: pkt.id.into(),
+                payload: pkt.payload,
+            });
+        }
+    }
+}
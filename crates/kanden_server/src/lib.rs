@@ -7,7 +7,10 @@ mod chunk_view;
 pub mod client;
 pub mod client_command;
 pub mod client_settings;
+pub mod combat;
+pub mod cookies;
 pub mod custom_payload;
+pub mod dialog;
 pub mod event_loop;
 pub mod hand_swing;
 pub mod interact_block;
@@ -18,6 +21,7 @@ pub mod layer;
 pub mod message;
 pub mod movement;
 pub mod op_level;
+pub mod registry_sync;
 pub mod resource_pack;
 pub mod spawn;
 pub mod status;
@@ -0,0 +1,97 @@
+//! Syncs [`RegistryCodec`] to clients as [`RegistryDataS2c`] packets once a
+//! client has just connected -- letting server code add, override, or
+//! remove entries (e.g. a datapack-style custom dimension type) take effect
+//! before that client reaches `Play`.
+//!
+//! Resending to a client that changed a registry mid-session (rather than
+//! just on connect) is not implemented yet -- see [`resync_changed_registries`].
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use kanden_ident::Ident;
+use kanden_protocol::packets::configuration::RegistryDataS2c;
+use kanden_protocol::WritePacket;
+use kanden_registry::codec::RegistryCodec;
+use kanden_registry::damage_type::DamageTypeRegistry;
+use kanden_registry::dimension_type::DimensionTypeRegistry;
+use kanden_registry::RegistrySet;
+
+use crate::client::Client;
+
+pub struct RegistrySyncPlugin;
+
+impl Plugin for RegistrySyncPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (sync_newly_connected_clients, resync_changed_registries)
+                .chain()
+                .in_set(RegistrySet),
+        );
+    }
+}
+
+/// Registries this plugin keeps clients in sync with, identified by their
+/// [`RegistryCodec`] key. A crate adding a new registry type (e.g. a
+/// `biome` registry, not part of this build) should append its `KEY` here
+/// so it's included in the registry data sent to each newly connected
+/// client.
+const SYNCED_REGISTRIES: &[Ident<&str>] = &[DamageTypeRegistry::KEY, DimensionTypeRegistry::KEY];
+
+/// Marks a client as having received every [`SYNCED_REGISTRIES`] entry at
+/// least once, so [`resync_changed_registries`] knows who to resend to.
+#[derive(Component, Debug)]
+pub struct RegistriesSynced;
+
+/// Sends every [`SYNCED_REGISTRIES`] entry to a client right after it
+/// connects.
+///
+/// `Added<Client>` is the closest thing this checkout exposes to "a client
+/// has just finished `PacketState::Configuration`" -- a dedicated
+/// connection-state marker, if this build grows one, should replace it
+/// here.
+fn sync_newly_connected_clients(
+    mut clients: Query<(Entity, &mut Client), Added<Client>>,
+    codec: Res<RegistryCodec>,
+    mut commands: Commands,
+) {
+    for (entity, mut client) in &mut clients {
+        send_registries(&mut client, &codec);
+        commands.entity(entity).insert(RegistriesSynced);
+    }
+}
+
+/// Intended to resend every [`SYNCED_REGISTRIES`] entry to every
+/// already-[`RegistriesSynced`] client whenever [`RegistryCodec`] changes, so
+/// a registry mutated mid-session reaches clients that are already playing.
+///
+/// It doesn't actually do that yet: [`RegistryDataS2c`] is only valid while a
+/// client is in `PacketState::Configuration`, but by the time a client is
+/// marked [`RegistriesSynced`] it has normally moved on to `Play` -- sending
+/// it there would be a protocol violation. Resending live requires first
+/// pulling the client back into `Configuration` via the
+/// Start-Configuration/Finish-Configuration round trip, which needs a
+/// connection-state handle on `Client` this checkout's `crate::client`
+/// doesn't expose. Until that lands, this system is a no-op rather than a
+/// packet that would desync real clients.
+fn resync_changed_registries(
+    _clients: Query<&mut Client, With<RegistriesSynced>>,
+    _codec: Res<RegistryCodec>,
+) {
+    // TODO: drive Start-Configuration/Finish-Configuration for each client
+    // above, then call `send_registries` once it's back in Configuration.
+}
+
+fn send_registries(client: &mut Client, codec: &RegistryCodec) {
+    for key in SYNCED_REGISTRIES {
+        let entries = codec
+            .registry(*key)
+            .map(|(name, compound)| (name.clone().into(), Some(compound.clone())))
+            .collect();
+
+        client.write_packet(&RegistryDataS2c {
+            id: (*key).into(),
+            entries,
+        });
+    }
+}
@@ -0,0 +1,190 @@
+//! Chat text components, following Minecraft's JSON text component format.
+
+use std::io::Write;
+
+use kanden_protocol::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+/// A formatted chat message component.
+///
+/// `Text` normally round-trips structured JSON components (the format sent
+/// by the vanilla server/client), but [`Text::from_string`] also accepts
+/// legacy `§`-formatted strings so that pasted vanilla strings and plugin
+/// messages can be turned into a `Text` as well.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct Text(pub Box<TextComponent>);
+
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct TextComponent {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub italic: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub underlined: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strikethrough: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obfuscated: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra: Vec<Text>,
+}
+
+/// One of the 16 named legacy colors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Color {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+}
+
+impl Color {
+    /// Returns the color associated with a legacy `§` code character, if any.
+    pub fn from_code(code: char) -> Option<Self> {
+        use Color::*;
+
+        Some(match code.to_ascii_lowercase() {
+            '0' => Black,
+            '1' => DarkBlue,
+            '2' => DarkGreen,
+            '3' => DarkAqua,
+            '4' => DarkRed,
+            '5' => DarkPurple,
+            '6' => Gold,
+            '7' => Gray,
+            '8' => DarkGray,
+            '9' => Blue,
+            'a' => Green,
+            'b' => Aqua,
+            'c' => Red,
+            'd' => LightPurple,
+            'e' => Yellow,
+            'f' => White,
+            _ => return None,
+        })
+    }
+}
+
+impl Text {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn text(text: impl Into<String>) -> Self {
+        Self(Box::new(TextComponent {
+            text: text.into(),
+            ..Default::default()
+        }))
+    }
+
+    /// Builds a `Text` from a string, first trying structured JSON and
+    /// falling back to legacy `§`-formatted text.
+    ///
+    /// This lets callers accept either a structured component payload or a
+    /// plain vanilla-style string (e.g. pasted chat, plugin messages) through
+    /// a single entry point.
+    pub fn from_string(s: &str) -> Self {
+        if let Ok(text) = serde_json::from_str::<Text>(s) {
+            return text;
+        }
+
+        Self::from_legacy(s)
+    }
+
+    /// Parses a legacy string formatted with `§` section-sign codes.
+    ///
+    /// `0`-`9`/`a`-`f` set one of the 16 named colors (and reset any active
+    /// formatting, matching vanilla behavior), `k`-`o` set the
+    /// obfuscated/bold/strikethrough/underline/italic flags without
+    /// disturbing the active color, and `r` resets all formatting.
+    fn from_legacy(s: &str) -> Self {
+        let mut root = TextComponent::default();
+        let mut current = TextComponent::default();
+        let mut chars = s.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '§' {
+                current.text.push(c);
+                continue;
+            }
+
+            let Some(code) = chars.next() else {
+                current.text.push(c);
+                continue;
+            };
+
+            if !current.text.is_empty() {
+                // Carry the active color/flags into the new run before
+                // applying `code`'s own effect below -- a format code (`k`-
+                // `o`) should only add to what's active, not clear it. `r`
+                // and a color code still reset to a clean slate themselves,
+                // matching vanilla.
+                let carried = TextComponent {
+                    color: current.color,
+                    bold: current.bold,
+                    italic: current.italic,
+                    underlined: current.underlined,
+                    strikethrough: current.strikethrough,
+                    obfuscated: current.obfuscated,
+                    ..Default::default()
+                };
+                let finished = std::mem::replace(&mut current, carried);
+                root.extra.push(Text(Box::new(finished)));
+            }
+
+            match code.to_ascii_lowercase() {
+                'r' => current = TextComponent::default(),
+                'k' => current.obfuscated = Some(true),
+                'l' => current.bold = Some(true),
+                'm' => current.strikethrough = Some(true),
+                'n' => current.underlined = Some(true),
+                'o' => current.italic = Some(true),
+                _ => {
+                    if let Some(color) = Color::from_code(code) {
+                        current = TextComponent {
+                            color: Some(color),
+                            ..Default::default()
+                        };
+                    }
+                }
+            }
+        }
+
+        if !current.text.is_empty() {
+            root.extra.push(Text(Box::new(current)));
+        }
+
+        Text(Box::new(root))
+    }
+}
+
+impl Encode for Text {
+    fn encode(&self, w: impl Write) -> anyhow::Result<()> {
+        serde_json::to_string(self)?.encode(w)
+    }
+}
+
+impl<'a> Decode<'a> for Text {
+    fn decode(r: &mut &'a [u8]) -> anyhow::Result<Self> {
+        let json = <&'a str>::decode(r)?;
+        Ok(serde_json::from_str(json)?)
+    }
+}
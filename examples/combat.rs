@@ -1,13 +1,9 @@
 #![allow(clippy::type_complexity)]
 
 use bevy_ecs::query::QueryData;
-use kanden::entity::living::DataHealth;
-use kanden::entity::{EntityId, EntityStatuses, OnGround, Velocity};
-use kanden::math::Vec3Swizzles;
-use kanden::protocol::lpvec::LpVec3;
-use kanden::protocol::packets::play::{DamageEventS2c, HurtAnimationS2c};
-use kanden::protocol::{Decode, Encode, VarInt, WritePacket};
-use kanden::{prelude::*, Layer};
+use kanden::entity::{EntityStatuses, Velocity};
+use kanden::protocol::{Decode, Encode};
+use kanden::{ident, prelude::*, Layer};
 use rand::Rng;
 
 const SPAWN_Y: i32 = 64;
@@ -16,8 +12,6 @@ const ARENA_RADIUS: i32 = 32;
 /// Attached to every client.
 #[derive(Component, Default)]
 struct CombatState {
-    /// The tick the client was last attacked.
-    last_attacked_tick: i64,
     has_bonus_knockback: bool,
 }
 
@@ -43,7 +37,10 @@ fn setup(
     server: Res<Server>,
     dimensions: Res<DimensionTypeRegistry>,
     biomes: Res<BiomeRegistry>,
+    mut damage_types: ResMut<DamageTypeRegistry>,
 ) {
+    damage_types.insert(ident!("generic").into(), DamageType::default());
+
     let mut layer = LayerBundle::new(ident!("overworld"), &dimensions, &biomes, &server);
 
     for z in -5..5 {
@@ -110,31 +107,25 @@ fn init_clients(
         pos.set([0.0, f64::from(SPAWN_Y) + 1.0, 0.0]);
         *game_mode = GameMode::Creative;
 
-        commands.entity(entity).insert(CombatState::default());
+        commands
+            .entity(entity)
+            .insert((CombatState::default(), Invulnerability::default()));
     }
 }
 
 #[derive(QueryData)]
 #[query_data(mutable)]
 struct CombatQuery {
-    id: &'static EntityId,
     client: &'static mut Client,
-    velocity: &'static mut Velocity,
-    look: &'static Look,
-    pos: &'static Position,
-    on_ground: &'static mut OnGround,
     state: &'static mut CombatState,
     statuses: &'static mut EntityStatuses,
-    health: &'static mut DataHealth,
-    layer: &'static mut VisibleChunkLayer,
 }
 
 fn handle_combat_events(
-    server: Res<Server>,
-    mut layers: Query<&mut ChunkLayer>,
     mut clients: Query<CombatQuery>,
     mut sprinting: EventReader<SprintEvent>,
     mut interact_entity: EventReader<InteractEntityEvent>,
+    mut apply_damage: EventWriter<ApplyDamage>,
 ) {
     for &SprintEvent { client, state } in sprinting.read() {
         if let Ok(mut client) = clients.get_mut(client) {
@@ -148,44 +139,24 @@ fn handle_combat_events(
         ..
     } in interact_entity.read()
     {
-        let Ok([mut attacker, mut victim]) = clients.get_many_mut([attacker_client, victim_client])
+        let Ok([mut attacker, _victim]) = clients.get_many_mut([attacker_client, victim_client])
         else {
             // Victim or attacker does not exist, or the attacker is attacking itself.
             continue;
         };
 
-        if server.current_tick() - victim.state.last_attacked_tick < 10 {
-            // Victim is still on attack cooldown.
-            continue;
-        }
-
-        victim.state.last_attacked_tick = server.current_tick();
-
-        let victim_pos = victim.pos.0.xz();
-        let attacker_pos = attacker.pos.0.xz();
-        let dir = (attacker_pos - victim_pos).normalize().as_vec2();
-
-        victim
-            .velocity
-            .apply_knockback(0.5, dir.x, dir.y, victim.on_ground.0);
-
         attacker.state.has_bonus_knockback = false;
 
-        let Ok(mut layer) = layers.get_mut(victim.layer.0) else {
-            return;
-        };
-
-        let mut layer_writer = layer.view_writer(victim.pos.0);
-        layer_writer.write_packet(&DamageEventS2c {
-            entity_id: VarInt(victim.id.get()),
-            source_type_id: VarInt(29),
-            source_cause_id: VarInt(-1),
-            source_direct_id: VarInt(-1),
-            source_pos: Some(attacker.pos.0.into()),
-        });
-        layer_writer.write_packet(&HurtAnimationS2c {
-            entity_id: VarInt(victim.id.get()),
-            yaw: victim.look.yaw,
+        // The invulnerability window and knockback scaling for `generic` come
+        // from the DamageType registered in `setup`; `Invulnerability` on the
+        // victim (inserted alongside `CombatState` in `init_clients`) keeps
+        // them from being hit again until that window has passed.
+        apply_damage.send(ApplyDamage {
+            target: victim_client,
+            attacker: Some(attacker_client),
+            amount: 1.0,
+            damage_type: ident!("generic").into(),
+            source_pos: None,
         });
     }
 }
@@ -36,6 +36,7 @@ mod tests;
 #[cfg(feature = "log")]
 pub use bevy_log as log;
 use registry::biome::BiomePlugin;
+use registry::damage_type::DamageTypePlugin;
 use registry::dimension_type::DimensionTypePlugin;
 #[cfg(feature = "advancement")]
 pub use kanden_advancement as advancement;
@@ -57,12 +58,17 @@ pub use kanden_player_list as player_list;
 use kanden_registry::RegistryPlugin;
 #[cfg(feature = "scoreboard")]
 pub use kanden_scoreboard as scoreboard;
+#[cfg(feature = "scripting")]
+pub use kanden_scripting as scripting;
 use kanden_server::abilities::AbilitiesPlugin;
 use kanden_server::action::ActionPlugin;
 use kanden_server::client::ClientPlugin;
 use kanden_server::client_command::ClientCommandPlugin;
 use kanden_server::client_settings::ClientSettingsPlugin;
+use kanden_server::combat::CombatPlugin;
+use kanden_server::cookies::CookiePlugin;
 use kanden_server::custom_payload::CustomPayloadPlugin;
+use kanden_server::dialog::DialogPlugin;
 use kanden_server::entity::hitbox::HitboxPlugin;
 use kanden_server::entity::EntityPlugin;
 use kanden_server::event_loop::EventLoopPlugin;
@@ -76,6 +82,7 @@ use kanden_server::message::MessagePlugin;
 use kanden_server::movement::MovementPlugin;
 use kanden_server::op_level::OpLevelPlugin;
 pub use kanden_server::protocol::status_effects;
+use kanden_server::registry_sync::RegistrySyncPlugin;
 use kanden_server::resource_pack::ResourcePackPlugin;
 use kanden_server::status::StatusPlugin;
 use kanden_server::status_effect::StatusEffectPlugin;
@@ -120,6 +127,7 @@ pub mod prelude {
     #[cfg(feature = "player_list")]
     pub use kanden_player_list::{PlayerList, PlayerListEntry};
     pub use kanden_registry::biome::{Biome, BiomeId, BiomeRegistry};
+    pub use kanden_registry::damage_type::{DamageType, DamageTypeId, DamageTypeRegistry};
     pub use kanden_registry::dimension_type::{DimensionType, DimensionTypeRegistry};
     pub use kanden_server::action::{DiggingEvent, DiggingState};
     pub use kanden_server::block::{BlockKind, BlockState, PropName, PropValue};
@@ -131,6 +139,11 @@ pub mod prelude {
         JumpWithHorseEvent, JumpWithHorseState, LeaveBedEvent, PlayerCommand, SneakEvent,
         SneakState, SprintEvent, SprintState,
     };
+    pub use kanden_server::combat::{ApplyDamage, Invulnerability};
+    pub use kanden_server::cookies::{
+        request_cookie, store_cookie, CookieReceived, Cookies, Transfer, TransferInitiated,
+    };
+    pub use kanden_server::dialog::{ClearDialog as _, DialogSubmitEvent, ShowDialog as _};
     pub use kanden_server::entity::hitbox::{Hitbox, HitboxShape};
     pub use kanden_server::entity::{
         EntityAnimation, EntityKind, EntityLayerId, EntityManager, EntityStatus, HeadYaw, Look,
@@ -148,6 +161,9 @@ pub mod prelude {
     pub use kanden_server::math::{DVec2, DVec3, Vec2, Vec3};
     pub use kanden_server::message::SendMessage as _;
     pub use kanden_server::nbt::Compound;
+    pub use kanden_server::protocol::packets::common::show_dialog_s2c::{
+        Dialog, DialogAction, DialogBase, DialogOption, InputField, SubmittedValue,
+    };
     pub use kanden_server::protocol::packets::play::level_particles_s2c::Particle;
     pub use kanden_server::protocol::text::{Color, IntoText, Text};
     pub use kanden_server::spawn::{ClientSpawnQuery, ClientSpawnQueryReadOnly, RespawnPosition};
@@ -176,6 +192,8 @@ impl PluginGroup for DefaultPlugins {
             .add(RegistryPlugin)
             .add(BiomePlugin)
             .add(DimensionTypePlugin)
+            .add(DamageTypePlugin)
+            .add(RegistrySyncPlugin)
             .add(EntityPlugin)
             .add(HitboxPlugin)
             .add(LayerPlugin)
@@ -186,10 +204,13 @@ impl PluginGroup for DefaultPlugins {
             .add(KeepalivePlugin)
             .add(InteractEntityPlugin)
             .add(ClientSettingsPlugin)
+            .add(CombatPlugin)
             .add(ActionPlugin)
             .add(TeleportPlugin)
             .add(MessagePlugin)
             .add(CustomPayloadPlugin)
+            .add(CookiePlugin)
+            .add(DialogPlugin)
             .add(HandSwingPlugin)
             .add(InteractBlockPlugin)
             .add(InteractItemPlugin)
@@ -254,6 +275,11 @@ impl PluginGroup for DefaultPlugins {
             group = group.add(kanden_scoreboard::ScoreboardPlugin)
         }
 
+        #[cfg(feature = "scripting")]
+        {
+            group = group.add(kanden_scripting::ScriptingPlugin::default())
+        }
+
         group
     }
 }
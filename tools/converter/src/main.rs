@@ -2,6 +2,12 @@ use std::path::PathBuf;
 
 use clap::{Parser, ValueEnum};
 
+mod pumpkin;
+
+/// Format of the extracted data being converted.
+///
+/// Adding a new extractor means adding a variant here and a matching arm in
+/// `main`; the rest of the CLI (source/output paths) is shared.
 #[derive(ValueEnum, Clone)]
 enum SourceFormat {
     Pumpkin,
@@ -21,14 +27,12 @@ struct Cli {
     output: PathBuf,
 }
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
 
     match args.source_format {
-        SourceFormat::Pumpkin => convert_from_pumpkin(args.source, args.output),
+        SourceFormat::Pumpkin => pumpkin::convert(&args.source, &args.output)?,
     }
-}
 
-fn convert_from_pumpkin(source: PathBuf, output: PathBuf) {
-    source.join("")
+    Ok(())
 }
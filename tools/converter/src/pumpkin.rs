@@ -0,0 +1,201 @@
+//! Converts data dumped by the [Pumpkin] extractor into the Rust/data
+//! artifacts `kanden_generated` consumes.
+//!
+//! [Pumpkin]: https://github.com/Pumpkin-MC/Pumpkin
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use kanden_ident::Ident;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+struct PumpkinBlockProperty {
+    name: String,
+    values: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PumpkinBlockState {
+    id: u32,
+    #[serde(default)]
+    properties: BTreeMap<String, String>,
+    #[serde(default)]
+    default: bool,
+}
+
+#[derive(Deserialize)]
+struct PumpkinBlock {
+    id: u32,
+    name: String,
+    #[serde(default)]
+    properties: Vec<PumpkinBlockProperty>,
+    states: Vec<PumpkinBlockState>,
+}
+
+#[derive(Deserialize)]
+struct PumpkinEntityKind {
+    id: u32,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct PumpkinRegistryEntry {
+    name: String,
+    #[serde(default)]
+    element: Value,
+}
+
+#[derive(Serialize)]
+struct ConvertedBlockState {
+    id: u32,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+    default: bool,
+}
+
+#[derive(Serialize)]
+struct ConvertedBlock {
+    id: u32,
+    name: Ident<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    properties: Vec<PumpkinBlockPropertyOwned>,
+    // Kept in source order, not resorted: this is what keeps block-state IDs
+    // stable across runs, since a state's ID is an index into this list on
+    // the `kanden_generated` side.
+    states: Vec<ConvertedBlockState>,
+}
+
+#[derive(Serialize)]
+struct PumpkinBlockPropertyOwned {
+    name: String,
+    values: Vec<String>,
+}
+
+/// Reads every `*.json` dump Pumpkin produces under `source` and writes the
+/// converted artifacts `kanden_generated` expects under `output`.
+///
+/// Every `Ident` referenced by the source data is validated as it's
+/// converted, so a malformed registry name fails the conversion instead of
+/// silently producing generated code that won't resolve. Output is written
+/// with sorted keys and stable field order so repeated runs over the same
+/// input produce byte-identical files and diffs stay reviewable.
+pub fn convert(source: &Path, output: &Path) -> Result<()> {
+    fs::create_dir_all(output)
+        .with_context(|| format!("failed to create output directory '{}'", output.display()))?;
+
+    convert_blocks(source, output)?;
+    convert_entity_kinds(source, output)?;
+    convert_registries(source, output)?;
+
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<T> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read '{}'", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("failed to parse '{}'", path.display()))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .with_context(|| format!("failed to serialize '{}'", path.display()))?;
+    fs::write(path, json).with_context(|| format!("failed to write '{}'", path.display()))
+}
+
+fn convert_blocks(source: &Path, output: &Path) -> Result<()> {
+    let blocks: Vec<PumpkinBlock> = read_json(&source.join("blocks.json"))?;
+
+    let mut converted = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let name = Ident::new(block.name.clone())
+            .with_context(|| format!("block '{}' has an invalid ident", block.name))?;
+
+        converted.push(ConvertedBlock {
+            id: block.id,
+            name,
+            properties: block
+                .properties
+                .into_iter()
+                .map(|property| PumpkinBlockPropertyOwned {
+                    name: property.name,
+                    values: property.values,
+                })
+                .collect(),
+            states: block
+                .states
+                .into_iter()
+                .map(|state| ConvertedBlockState {
+                    id: state.id,
+                    properties: state.properties,
+                    default: state.default,
+                })
+                .collect(),
+        });
+    }
+
+    // Sort by registry ID rather than name: this is the order `blocks.json`
+    // is re-read in downstream, and re-sorting by name here would desync it
+    // from the (stable) numeric ID space the rest of the pipeline uses.
+    converted.sort_by_key(|block| block.id);
+
+    write_json(&output.join("blocks.json"), &converted)
+}
+
+fn convert_entity_kinds(source: &Path, output: &Path) -> Result<()> {
+    let entities: Vec<PumpkinEntityKind> = read_json(&source.join("entities.json"))?;
+
+    let mut converted: Vec<(u32, Ident<String>)> = entities
+        .into_iter()
+        .map(|entity| {
+            let name = Ident::new(entity.name.clone())
+                .with_context(|| format!("entity kind '{}' has an invalid ident", entity.name))?;
+            Ok((entity.id, name))
+        })
+        .collect::<Result<_>>()?;
+
+    converted.sort_by_key(|(id, _)| *id);
+
+    // Keyed by name with the numeric ID as value: the shape the `EntityKind`
+    // build script in `kanden_generated` reads.
+    let converted: BTreeMap<Ident<String>, u32> =
+        converted.into_iter().map(|(id, name)| (name, id)).collect();
+
+    write_json(&output.join("entities.json"), &converted)
+}
+
+/// Registries such as biomes and dimension types (and anything else Pumpkin
+/// dumps as a flat `{ id, name, element }` list) are folded into a single
+/// `registry_codec.json`, matching the shape `kanden_registry` already reads
+/// its registry codec from.
+fn convert_registries(source: &Path, output: &Path) -> Result<()> {
+    const REGISTRIES: &[(&str, &str)] = &[
+        ("biomes.json", "minecraft:worldgen/biome"),
+        ("dimension_types.json", "minecraft:dimension_type"),
+    ];
+
+    let mut codec: BTreeMap<&str, BTreeMap<Ident<String>, Value>> = BTreeMap::new();
+
+    for (file, registry_key) in REGISTRIES {
+        let path = source.join(file);
+        if !path.exists() {
+            continue;
+        }
+
+        let entries: Vec<PumpkinRegistryEntry> = read_json(&path)?;
+        let mut registry = BTreeMap::new();
+
+        for entry in entries {
+            let name = Ident::new(entry.name.clone())
+                .with_context(|| format!("registry entry '{}' has an invalid ident", entry.name))?;
+            registry.insert(name, entry.element);
+        }
+
+        codec.insert(registry_key, registry);
+    }
+
+    write_json(&output.join("registry_codec.json"), &codec)
+}
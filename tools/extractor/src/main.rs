@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     env,
     fs::File,
     io::{self, Read, Write},
@@ -8,14 +9,67 @@ use std::{
 };
 
 use clap::Parser;
-use git2::{build::RepoBuilder, FetchOptions, RemoteCallbacks, Repository};
-use log::{error, info};
+use git2::{
+    build::{CheckoutBuilder, RepoBuilder},
+    FetchOptions, RemoteCallbacks, Repository,
+};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 #[derive(clap::Parser)]
 #[clap(version)]
 struct Args {
     #[clap(long, default_value_t = false)]
     copy_only: bool,
+    /// Minecraft version to extract data for, e.g. `1.21.4`.
+    ///
+    /// Selects the matching branch/tag on the extractor repository, and is
+    /// recorded in each destination's `manifest.json` so a later
+    /// `--copy-only` run for a *different* version doesn't silently mix
+    /// data from two versions into the same directory.
+    #[clap(long)]
+    version: Option<String>,
+}
+
+/// One destination directory's extraction record, keyed by the copied
+/// file's name.
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestEntry {
+    sha256: String,
+    mc_version: String,
+}
+
+fn sha256_hex(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn load_manifest(dst_dir: &Path) -> Manifest {
+    let path = dst_dir.join("manifest.json");
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return Manifest::default();
+    };
+
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        warn!("'{}' is corrupted, ignoring it: {e}", path.display());
+        Manifest::default()
+    })
+}
+
+fn save_manifest(dst_dir: &Path, manifest: &Manifest) {
+    let path = dst_dir.join("manifest.json");
+    let json =
+        serde_json::to_string_pretty(manifest).expect("manifest should always be serializable");
+    std::fs::write(&path, json)
+        .unwrap_or_else(|e| panic!("failed to write '{}': {e}", path.display()));
 }
 
 const EXTRACTOR_REPOSITORY_URL: &str = "https://github.com/kappa8719/kanden-extractor.git";
@@ -66,6 +120,16 @@ fn update_extractor(dst: &Path, branch: &str) -> Result<git2::Repository, git2::
 
                 remote.fetch(&[branch], Some(&mut fetch_options), None)?;
             }
+
+            // `fetch` only updates refs/objects -- it never touches the
+            // working tree, so without this a second run with a different
+            // `--version`/branch would still extract from whatever tree the
+            // original clone checked out.
+            let fetch_head = repo.find_reference("FETCH_HEAD")?;
+            let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
+            repo.set_head_detached(fetch_commit.id())?;
+            repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+
             return Ok(repo);
         } else {
             error!("the extractor is corrupted. delete '.extractor' to fetch the extractor again");
@@ -76,16 +140,46 @@ fn update_extractor(dst: &Path, branch: &str) -> Result<git2::Repository, git2::
     info!("cloning extractor");
     let mut builder = RepoBuilder::new();
     builder.fetch_options(fetch_options);
+    builder.branch(branch);
     return builder.clone(EXTRACTOR_REPOSITORY_URL, dst);
 }
 
-fn copy_files(target: &Path) {
+fn copy_files(target: &Path, mc_version: Option<&str>) {
+    let mc_version = mc_version.unwrap_or("unspecified");
+
     for (src, dst) in COPIES {
         let src_path = target.join("run/_data").join(src);
-        let dst_path = Path::new(dst).join(src);
+        let dst_dir = Path::new(dst);
+        let dst_path = dst_dir.join(src);
+
+        let mut manifest = load_manifest(dst_dir);
+
+        if let Some(existing) = manifest.files.get(*src) {
+            if existing.mc_version != mc_version {
+                error!(
+                    "refusing to overwrite '{}': it was extracted for mc {}, not requested {mc_version}",
+                    dst_path.display(),
+                    existing.mc_version
+                );
+                continue;
+            }
+        }
+
         std::fs::copy(src_path.as_path(), dst_path.as_path()).unwrap_or_else(|e| {
             panic!("failed to copy from '{src_path:?}' to '{dst_path:?}': {e}")
         });
+
+        let sha256 = sha256_hex(&dst_path)
+            .unwrap_or_else(|e| panic!("failed to hash '{}': {e}", dst_path.display()));
+
+        manifest.files.insert(
+            src.to_string(),
+            ManifestEntry {
+                sha256,
+                mc_version: mc_version.to_string(),
+            },
+        );
+        save_manifest(dst_dir, &manifest);
     }
 }
 
@@ -101,13 +195,14 @@ fn main() {
 
     if args.copy_only {
         info!("copy only: copying files");
-        copy_files(target);
+        copy_files(target, args.version.as_deref());
         info!("complete");
         return;
     }
 
-    update_extractor(target, "main").unwrap_or_else(|e| {
-        panic!("failed to clone extractor from {EXTRACTOR_REPOSITORY_URL}: {e}")
+    let branch = args.version.as_deref().unwrap_or("main");
+    update_extractor(target, branch).unwrap_or_else(|e| {
+        panic!("failed to clone extractor from {EXTRACTOR_REPOSITORY_URL} at '{branch}': {e}")
     });
 
     let eula = target.join("run/eula.txt");
@@ -141,7 +236,7 @@ fn main() {
     if status.success() {
         info!("extraction complete");
         info!("copying files");
-        copy_files(target);
+        copy_files(target, args.version.as_deref());
         info!("copied files");
     } else {
         error!("extraction failed with status {status}");
@@ -0,0 +1,55 @@
+//! Dispatches a decoded vanilla packet name to the concrete `Decode` impl
+//! this checkout happens to have, if any.
+//!
+//! `registry::table` only gets us as far as a name — most of those names
+//! don't have a corresponding struct in this checkout (protocol coverage is
+//! partial), so lookups that miss here fall back to a raw hex dump in
+//! `proxy`.
+
+use kanden_protocol::packets::common::{ClearDialogS2c, CustomClickActionC2s, ShowDialogS2c};
+use kanden_protocol::packets::configuration::{ClientInformationC2s, CodeOfConductS2c};
+use kanden_protocol::packets::play::{
+    AddEntityS2c, ChangeGameModeC2s, CookieRequestS2c, CookieResponseC2s, EntityPositionSyncS2c,
+    PlayerCombatKillS2c, PlayerCommandC2s, PlayerPositionS2c, StoreCookieS2c, TeleportEntityS2c,
+    TransferS2c,
+};
+use kanden_protocol::Decode;
+
+/// Produces a `Debug` dump of `body` as `T`, for use as a [`Decoder`] entry.
+fn decode_as<'a, T: Decode<'a> + std::fmt::Debug>(body: &mut &'a [u8]) -> anyhow::Result<String> {
+    Ok(format!("{:#?}", T::decode(body)?))
+}
+
+type Decoder = for<'a> fn(&mut &'a [u8]) -> anyhow::Result<String>;
+
+macro_rules! decoders {
+    ($(($name:literal, $ty:ty)),* $(,)?) => {
+        /// Looks up a decoder by the packet's vanilla resource name (as
+        /// reported in `packets.json`), e.g. `"minecraft:transfer"`.
+        pub fn lookup(name: &str) -> Option<Decoder> {
+            match name {
+                $($name => Some(decode_as::<$ty>),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+decoders! {
+    ("minecraft:show_dialog", ShowDialogS2c),
+    ("minecraft:clear_dialog", ClearDialogS2c),
+    ("minecraft:custom_click_action", CustomClickActionC2s),
+    ("minecraft:client_information", ClientInformationC2s),
+    ("minecraft:code_of_conduct", CodeOfConductS2c),
+    ("minecraft:add_entity", AddEntityS2c),
+    ("minecraft:change_game_mode", ChangeGameModeC2s),
+    ("minecraft:cookie_request", CookieRequestS2c),
+    ("minecraft:cookie_response", CookieResponseC2s),
+    ("minecraft:store_cookie", StoreCookieS2c),
+    ("minecraft:transfer", TransferS2c),
+    ("minecraft:entity_position_sync", EntityPositionSyncS2c),
+    ("minecraft:player_position", PlayerPositionS2c),
+    ("minecraft:player_combat_kill", PlayerCombatKillS2c),
+    ("minecraft:player_command", PlayerCommandC2s),
+    ("minecraft:teleport_entity", TeleportEntityS2c),
+}
@@ -0,0 +1,57 @@
+//! A transparent client <-> server MITM proxy that decodes every packet it
+//! relays using `kanden_protocol`'s own `Packet`/`Decode` machinery.
+//!
+//! Point a vanilla client at `--listen` instead of the real server and pass
+//! the real server's address as `--server`; every frame forwarded in either
+//! direction gets logged, decoded where this checkout has a struct for it
+//! and dumped as raw hex otherwise. See [`proxy`] for the relay itself and
+//! [`state`] for how the connection's `PacketState` is tracked without
+//! being one of the two real endpoints.
+
+mod decode;
+mod proxy;
+mod registry;
+mod state;
+
+use clap::Parser;
+
+use proxy::Filter;
+
+/// Logs every packet kanden's generated data knows the targeted vanilla
+/// version has, decoding it when this checkout has implemented the packet.
+#[derive(Parser)]
+#[clap(version)]
+struct Args {
+    /// Address to accept the client connection on, e.g. `127.0.0.1:25565`.
+    #[clap(long, default_value = "127.0.0.1:25565")]
+    listen: String,
+    /// Address of the real server to relay to.
+    #[clap(long)]
+    server: String,
+    /// Only log packets whose vanilla name contains one of these substrings.
+    #[clap(long = "filter")]
+    filters: Vec<String>,
+    /// Only log packets with one of these numeric ids (decimal or `0x..`).
+    #[clap(long = "filter-id", value_parser = parse_id)]
+    filter_ids: Vec<i32>,
+}
+
+fn parse_id(s: &str) -> Result<i32, std::num::ParseIntError> {
+    match s.strip_prefix("0x") {
+        Some(hex) => i32::from_str_radix(hex, 16),
+        None => s.parse(),
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    proxy::run(
+        &args.listen,
+        &args.server,
+        Filter {
+            substrings: args.filters,
+            ids: args.filter_ids,
+        },
+    )
+}
@@ -0,0 +1,261 @@
+//! The actual client <-> server relay.
+//!
+//! Every accepted client connection gets a matching connection to the real
+//! server and two relay threads, one per direction. Each thread reads one
+//! length-prefixed frame at a time, decodes it for logging, then forwards
+//! the *original* bytes unmodified so the two real endpoints never notice
+//! the proxy is there.
+//!
+//! Scope: this targets the common "point a vanilla client at a dev server"
+//! debugging case, so it assumes an offline-mode connection with
+//! compression disabled (the same assumption `kanden_protocol`'s tests make
+//! about frame shape) -- encryption and compression negotiation aren't
+//! handled, and a frame under either would just fail to decode and fall
+//! back to the raw hex dump below.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+
+use kanden_protocol::{Decode, Encode, VarInt};
+
+use crate::decode;
+use crate::registry;
+use crate::state::{read_handshake_next_state, ConnectionState, PacketDirection};
+
+/// Which packets get logged (decoded, or as raw hex); packets that don't
+/// match aren't printed at all.
+///
+/// Empty means "everything" -- matches every packet and every id.
+#[derive(Clone, Default)]
+pub struct Filter {
+    pub substrings: Vec<String>,
+    pub ids: Vec<i32>,
+}
+
+impl Filter {
+    fn matches(&self, name: Option<&str>, id: i32) -> bool {
+        if self.substrings.is_empty() && self.ids.is_empty() {
+            return true;
+        }
+
+        let name_matches = name
+            .map(|name| {
+                self.substrings
+                    .iter()
+                    .any(|needle| name.contains(needle.as_str()))
+            })
+            .unwrap_or(false);
+
+        name_matches || self.ids.contains(&id)
+    }
+}
+
+/// Accepts clients on `listen_addr` and relays each to `server_addr`.
+pub fn run(listen_addr: &str, server_addr: &str, filter: Filter) -> anyhow::Result<()> {
+    let listener = std::net::TcpListener::bind(listen_addr)?;
+    println!("listening on {listen_addr}, forwarding to {server_addr}");
+
+    for incoming in listener.incoming() {
+        let client = incoming?;
+        let server_addr = server_addr.to_socket_addrs()?.next().ok_or_else(|| {
+            anyhow::anyhow!("could not resolve server address '{server_addr}'")
+        })?;
+        let filter = filter.clone();
+
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(client, server_addr, filter) {
+                eprintln!("connection closed: {e:#}");
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle_connection(
+    client: TcpStream,
+    server_addr: impl ToSocketAddrs,
+    filter: Filter,
+) -> anyhow::Result<()> {
+    let server = TcpStream::connect(server_addr)?;
+    println!("accepted connection from {:?}", client.peer_addr());
+
+    let state = std::sync::Arc::new(std::sync::Mutex::new(ConnectionState::Handshaking));
+    let pending_next_state = std::sync::Arc::new(std::sync::Mutex::new(None));
+
+    let client_to_server = {
+        let client = client.try_clone()?;
+        let server = server.try_clone()?;
+        let state = state.clone();
+        let pending_next_state = pending_next_state.clone();
+        let filter = filter.clone();
+
+        thread::spawn(move || {
+            relay(
+                client,
+                server,
+                PacketDirection::Serverbound,
+                state,
+                pending_next_state,
+                filter,
+            )
+        })
+    };
+
+    let server_to_client = {
+        let state = state.clone();
+        thread::spawn(move || {
+            relay(
+                server,
+                client,
+                PacketDirection::Clientbound,
+                state,
+                pending_next_state,
+                filter,
+            )
+        })
+    };
+
+    let _ = client_to_server.join();
+    let _ = server_to_client.join();
+
+    Ok(())
+}
+
+type SharedState = std::sync::Arc<std::sync::Mutex<ConnectionState>>;
+type SharedNextState = std::sync::Arc<std::sync::Mutex<Option<i32>>>;
+
+fn relay(
+    mut read_from: TcpStream,
+    mut write_to: TcpStream,
+    direction: PacketDirection,
+    state: SharedState,
+    pending_next_state: SharedNextState,
+    filter: Filter,
+) -> anyhow::Result<()> {
+    loop {
+        let frame = match read_frame(&mut read_from) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        write_to.write_all(&frame)?;
+        write_to.flush()?;
+
+        let mut body = frame.as_slice();
+        // Skip the length prefix `read_frame` keeps for forwarding.
+        if VarInt::decode(&mut body).is_err() {
+            continue;
+        }
+        let Ok(packet_id) = VarInt::decode(&mut body) else {
+            continue;
+        };
+        let packet_id = packet_id.0;
+
+        let current_state = *state.lock().unwrap();
+
+        let mut handshake_peek = body;
+        if let Some(next) = read_handshake_next_state(packet_id, &mut handshake_peek) {
+            *pending_next_state.lock().unwrap() = Some(next);
+        }
+
+        let name = registry::table().name(current_state, direction, packet_id);
+
+        if filter.matches(name, packet_id) {
+            log_packet(direction, current_state, packet_id, name, body);
+        }
+
+        let next_state_hint = *pending_next_state.lock().unwrap();
+        let advanced = current_state.advance(direction, packet_id, next_state_hint);
+        if advanced != current_state {
+            *state.lock().unwrap() = advanced;
+        }
+    }
+}
+
+fn log_packet(
+    direction: PacketDirection,
+    state: ConnectionState,
+    packet_id: i32,
+    name: Option<&str>,
+    body: &[u8],
+) {
+    let label = name.unwrap_or("<unknown>");
+    print!("[{}] {state:?} 0x{packet_id:02x} {label}", direction.label());
+
+    match name.and_then(decode::lookup) {
+        Some(decoder) => {
+            let mut cursor = body;
+            match decoder(&mut cursor) {
+                Ok(decoded) => println!("\n{decoded}"),
+                Err(e) => println!(" (failed to decode: {e:#}, {} bytes raw)", body.len()),
+            }
+        }
+        None => println!(" ({} bytes: {})", body.len(), hex(body)),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    const MAX: usize = 64;
+    let truncated = &bytes[..bytes.len().min(MAX)];
+    let mut out = truncated
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if bytes.len() > MAX {
+        out.push_str(" ...");
+    }
+
+    out
+}
+
+/// Reads one length-prefixed frame (the VarInt length prefix, then that
+/// many bytes) and returns it as sent, length prefix included, so it can be
+/// forwarded unmodified.
+///
+/// Returns `Ok(None)` on a clean EOF between frames.
+fn read_frame(stream: &mut TcpStream) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(len) = read_varint(stream)? else {
+        return Ok(None);
+    };
+
+    let mut framed = Vec::new();
+    VarInt(len).encode(&mut framed)?;
+
+    let mut body = vec![0u8; len as usize];
+    stream.read_exact(&mut body)?;
+    framed.extend_from_slice(&body);
+
+    Ok(Some(framed))
+}
+
+/// Reads a single VarInt directly off the stream, one byte at a time (the
+/// length prefix isn't itself inside a length-prefixed frame, so it can't
+/// be read through `kanden_protocol::Decode`, which only decodes out of an
+/// already-buffered slice).
+///
+/// Returns `Ok(None)` if the stream is closed before any byte of the
+/// VarInt arrives.
+fn read_varint(stream: &mut TcpStream) -> anyhow::Result<Option<i32>> {
+    let mut value: i32 = 0;
+    for i in 0..5 {
+        let mut byte = [0u8; 1];
+        match stream.read(&mut byte) {
+            Ok(0) => return Ok(None),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+
+        value |= i32::from(byte[0] & 0x7f) << (i * 7);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+
+    Err(anyhow::anyhow!("VarInt too large"))
+}
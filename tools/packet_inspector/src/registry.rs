@@ -0,0 +1,91 @@
+//! The id -> name table the proxy decodes packet ids against, built from
+//! the extractor's `packets.json` (see the `extractor` tool's `COPIES`
+//! list, which also lands a copy under `tools/packet_inspector/extracted`
+//! for exactly this purpose).
+//!
+//! This is deliberately a *separate* table from
+//! `kanden_protocol::packet_registry`'s `PacketRegistration` list: that list
+//! only covers the packets this checkout has hand-written structs for,
+//! while `packets.json` enumerates every packet the targeted vanilla
+//! version actually has, so an unimplemented packet still prints under its
+//! real name instead of falling straight to a raw hex dump.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::state::{ConnectionState, PacketDirection};
+
+/// Shape of a single `packets.json` entry, mirroring the vanilla data
+/// generator's report: `{state: {direction: {packet_name: {protocol_id}}}}`.
+#[derive(Deserialize)]
+struct PacketsJson {
+    handshake: StateEntry,
+    status: StateEntry,
+    login: StateEntry,
+    configuration: StateEntry,
+    play: StateEntry,
+}
+
+#[derive(Deserialize, Default)]
+struct StateEntry {
+    #[serde(default)]
+    clientbound: HashMap<String, ProtocolId>,
+    #[serde(default)]
+    serverbound: HashMap<String, ProtocolId>,
+}
+
+#[derive(Deserialize)]
+struct ProtocolId {
+    protocol_id: i32,
+}
+
+/// `(state, direction, id) -> vanilla packet name`, e.g.
+/// `(Play, Clientbound, 0x1d) -> "minecraft:transfer"`.
+pub struct PacketTable {
+    names: HashMap<(ConnectionState, PacketDirection, i32), String>,
+}
+
+impl PacketTable {
+    pub fn name(&self, state: ConnectionState, direction: PacketDirection, id: i32) -> Option<&str> {
+        self.names
+            .get(&(state, direction, id))
+            .map(String::as_str)
+    }
+}
+
+fn insert_state(
+    names: &mut HashMap<(ConnectionState, PacketDirection, i32), String>,
+    state: ConnectionState,
+    entry: &StateEntry,
+) {
+    for (name, id) in &entry.clientbound {
+        names.insert((state, PacketDirection::Clientbound, id.protocol_id), name.clone());
+    }
+    for (name, id) in &entry.serverbound {
+        names.insert((state, PacketDirection::Serverbound, id.protocol_id), name.clone());
+    }
+}
+
+/// Loads the table baked in from `extracted/packets.json` at build time.
+///
+/// Parsed once and cached: the table never changes for the lifetime of the
+/// process, and every decoded frame needs a lookup.
+pub fn table() -> &'static PacketTable {
+    static TABLE: OnceLock<PacketTable> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let raw: PacketsJson = serde_json::from_str(include_str!("../extracted/packets.json"))
+            .expect("extracted/packets.json should be valid (run the extractor first)");
+
+        let mut names = HashMap::new();
+        insert_state(&mut names, ConnectionState::Handshaking, &raw.handshake);
+        insert_state(&mut names, ConnectionState::Status, &raw.status);
+        insert_state(&mut names, ConnectionState::Login, &raw.login);
+        insert_state(&mut names, ConnectionState::Configuration, &raw.configuration);
+        insert_state(&mut names, ConnectionState::Play, &raw.play);
+
+        PacketTable { names }
+    })
+}
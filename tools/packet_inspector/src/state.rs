@@ -0,0 +1,112 @@
+//! The connection state machine the proxy tracks per intercepted TCP stream.
+//!
+//! Unlike a real server, the proxy never parses the full body of most
+//! packets, so it can't rely on its own game logic to know when a
+//! connection moves between states — instead it watches for the handful
+//! of packets that *cause* a transition (the same ones `kanden_protocol`
+//! marks via `#[packet(state = ...)]`, e.g. `CodeOfConductS2c`/
+//! `ClientInformationC2s` for `PacketState::Configuration`) and advances a
+//! copy of the state machine alongside the two real endpoints.
+
+use std::borrow::Cow;
+
+use kanden_protocol::{Decode, PacketState, VarInt};
+
+/// Direction a packet travelled, from the proxy's point of view.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PacketDirection {
+    /// Client to server.
+    Serverbound,
+    /// Server to client.
+    Clientbound,
+}
+
+impl PacketDirection {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Serverbound => "C->S",
+            Self::Clientbound => "S->C",
+        }
+    }
+}
+
+/// Mirrors `kanden_protocol::PacketState`, plus the pre-login states that
+/// only exist on the wire (`kanden_protocol` only needs to distinguish
+/// states packets are actually registered in).
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ConnectionState {
+    Handshaking,
+    Status,
+    Login,
+    Configuration,
+    Play,
+}
+
+impl ConnectionState {
+    /// The `kanden_protocol::PacketState` this maps to, if any — handshake
+    /// and login packets aren't part of that enum since nothing in the
+    /// main crate registers packets for them individually.
+    pub fn packet_state(self) -> Option<PacketState> {
+        match self {
+            Self::Handshaking | Self::Status | Self::Login => None,
+            Self::Configuration => Some(PacketState::Configuration),
+            Self::Play => Some(PacketState::Play),
+        }
+    }
+
+    /// Advances the state machine after observing `(direction, packet_id)`
+    /// in this state, given the handshake's requested next state (only
+    /// relevant while still `Handshaking`).
+    pub fn advance(
+        self,
+        direction: PacketDirection,
+        packet_id: i32,
+        handshake_next_state: Option<i32>,
+    ) -> Self {
+        match (self, direction, packet_id) {
+            // Handshake (serverbound, id 0x00) carries the next state as a
+            // trailing VarInt (1 = Status, 2 = Login, 3 = Transfer/Login).
+            (Self::Handshaking, PacketDirection::Serverbound, 0x00) => {
+                match handshake_next_state {
+                    Some(1) => Self::Status,
+                    Some(2) | Some(3) => Self::Login,
+                    _ => self,
+                }
+            }
+            // Login Success (clientbound, id 0x02) is immediately followed
+            // by Login Acknowledged (serverbound, id 0x03), which is what
+            // actually switches the wire to Configuration.
+            (Self::Login, PacketDirection::Serverbound, 0x03) => Self::Configuration,
+            // Finish Configuration (clientbound, id 0x03) is acknowledged by
+            // the client with the same id serverbound, which is what
+            // switches the wire to Play.
+            (Self::Configuration, PacketDirection::Serverbound, 0x03) => Self::Play,
+            // A transfer sends the client back through Handshaking on a new
+            // connection, but on an existing one the server can also push
+            // it back into Configuration to edit registries/resource packs.
+            (Self::Play, PacketDirection::Clientbound, id) if id == CONFIGURATION_RESTART_ID => {
+                Self::Configuration
+            }
+            _ => self,
+        }
+    }
+}
+
+/// Clientbound `Start Configuration` packet ID, sent from `Play` to return
+/// the client to `Configuration` (e.g. to resync registries).
+const CONFIGURATION_RESTART_ID: i32 = 0x0F;
+
+/// Reads the trailing `next_state` VarInt off a (serverbound, Handshaking)
+/// frame, if `packet_id` is the handshake packet.
+pub fn read_handshake_next_state<'a>(packet_id: i32, body: &mut &'a [u8]) -> Option<i32> {
+    if packet_id != 0x00 {
+        return None;
+    }
+
+    // protocol_version: VarInt, server_address: String, server_port: u16,
+    // next_state: VarInt -- we only need the last field.
+    VarInt::decode(body).ok()?;
+    Cow::<'a, str>::decode(body).ok()?;
+    u16::decode(body).ok()?;
+    VarInt::decode(body).ok().map(|v| v.0)
+}